@@ -0,0 +1,85 @@
+use gtk::prelude::*;
+use gtk::{Box, Button, ComboBoxText, MenuButton, Orientation, Popover};
+
+use todo_core::BoardRegistry;
+
+/// Header controls for picking the board to show and for creating, renaming
+/// and archiving boards.
+pub struct BoardSwitcher {
+    pub container: Box,
+    pub combo: ComboBoxText,
+    pub new_button: Button,
+    pub rename_button: Button,
+    pub archive_button: Button,
+    pub unarchive_button: Button,
+    menu_button: MenuButton,
+}
+
+impl BoardSwitcher {
+    pub fn new() -> Self {
+        let container = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(5)
+            .valign(gtk::Align::Center)
+            .build();
+        container.add_css_class("board-switcher");
+
+        let combo = ComboBoxText::new();
+        combo.set_tooltip_text(Some("Switch board"));
+        combo.add_css_class("filter-combo");
+
+        let menu_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(5)
+            .build();
+        let menu_item = |label: &str| {
+            let button = Button::builder().label(label).build();
+            button.add_css_class("flat");
+            menu_box.append(&button);
+            button
+        };
+        let new_button = menu_item("New Board…");
+        let rename_button = menu_item("Rename Board…");
+        let archive_button = menu_item("Archive Board");
+        let unarchive_button = menu_item("Unarchive Board…");
+
+        let menu_button = MenuButton::builder()
+            .icon_name("view-more-symbolic")
+            .tooltip_text("Board actions")
+            .popover(&Popover::builder().child(&menu_box).build())
+            .build();
+        menu_button.add_css_class("action-button-small");
+
+        container.append(&combo);
+        container.append(&menu_button);
+
+        BoardSwitcher {
+            container,
+            combo,
+            new_button,
+            rename_button,
+            archive_button,
+            unarchive_button,
+            menu_button,
+        }
+    }
+
+    /// Lists the boards that are not archived and selects the active one.
+    pub fn set_boards(&self, boards: &BoardRegistry) {
+        self.combo.remove_all();
+        for board in boards.visible_boards() {
+            self.combo.append(Some(&board.name), &board.name);
+        }
+        self.combo.set_active_id(Some(&boards.active_board().name));
+
+        self.archive_button
+            .set_sensitive(boards.visible_boards().nth(1).is_some());
+        self.unarchive_button
+            .set_sensitive(boards.boards.iter().any(|b| b.archived));
+    }
+
+    /// Closes the actions menu after one of its buttons was used.
+    pub fn close_menu(&self) {
+        self.menu_button.popdown();
+    }
+}
@@ -0,0 +1,219 @@
+use glib::clone;
+use gtk::gdk;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::{
+    ApplicationWindow, Box, EventControllerKey, Label, ListBox, Orientation, ScrolledWindow,
+    SearchEntry, Window,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use todo_core::{FuzzyMatch, fuzzy_match};
+
+/// Commands listed at once; typing more narrows them down.
+const MAX_RESULTS: usize = 50;
+
+/// A line of the palette and what choosing it does.
+pub struct Command {
+    pub title: String,
+    /// Shown after the title, such as the command's shortcut.
+    pub hint: String,
+    pub run: std::boxed::Box<dyn Fn()>,
+}
+
+impl Command {
+    pub fn new(
+        title: impl Into<String>,
+        hint: impl Into<String>,
+        run: impl Fn() + 'static,
+    ) -> Self {
+        Command {
+            title: title.into(),
+            hint: hint.into(),
+            run: std::boxed::Box::new(run),
+        }
+    }
+}
+
+/// A window for finding a command by typing part of its name, opened over
+/// the main window. Up and Down pick a command, Enter runs it and Escape
+/// closes the palette.
+pub struct CommandPalette {
+    window: Window,
+    entry: SearchEntry,
+    results: ListBox,
+    scrolled: ScrolledWindow,
+    commands: RefCell<Vec<Command>>,
+    /// Indices into `commands` of the rows shown, best match first.
+    shown: RefCell<Vec<usize>>,
+}
+
+impl CommandPalette {
+    pub fn new(parent: &ApplicationWindow) -> Rc<Self> {
+        let entry = SearchEntry::builder()
+            .placeholder_text("Type a command or a task")
+            .build();
+        let results = ListBox::builder().can_focus(false).build();
+        results.add_css_class("palette-results");
+        let scrolled = ScrolledWindow::builder()
+            .child(&results)
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .propagate_natural_height(true)
+            .max_content_height(400)
+            .build();
+
+        let vbox = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(6)
+            .build();
+        vbox.add_css_class("command-palette");
+        vbox.append(&entry);
+        vbox.append(&scrolled);
+
+        let window = Window::builder()
+            .transient_for(parent)
+            .modal(true)
+            .decorated(false)
+            .hide_on_close(true)
+            .default_width(560)
+            .child(&vbox)
+            .build();
+
+        let palette = Rc::new(CommandPalette {
+            window,
+            entry,
+            results,
+            scrolled,
+            commands: RefCell::default(),
+            shown: RefCell::default(),
+        });
+
+        palette.entry.connect_search_changed(clone!(
+            #[weak]
+            palette,
+            move |_| palette.update()
+        ));
+        palette.entry.connect_activate(clone!(
+            #[weak]
+            palette,
+            move |_| {
+                let selected = palette.results.selected_row();
+                palette.run(selected.map_or(0, |row| row.index() as usize));
+            }
+        ));
+        palette.entry.connect_stop_search(clone!(
+            #[weak]
+            palette,
+            move |_| palette.window.close()
+        ));
+        palette.results.connect_row_activated(clone!(
+            #[weak]
+            palette,
+            move |_, row| palette.run(row.index() as usize)
+        ));
+
+        // Before the entry sees them, which would move the focus
+        let keys = EventControllerKey::new();
+        keys.set_propagation_phase(gtk::PropagationPhase::Capture);
+        keys.connect_key_pressed(clone!(
+            #[weak]
+            palette,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_, key, _, _| palette.handle_key(key)
+        ));
+        palette.entry.add_controller(keys);
+
+        palette
+    }
+
+    /// Shows the palette offering `commands`, in the order given until
+    /// something is typed.
+    pub fn open(&self, commands: Vec<Command>) {
+        *self.commands.borrow_mut() = commands;
+        self.entry.set_text("");
+        self.update();
+        self.window.present();
+        self.entry.grab_focus();
+    }
+
+    /// Lists the commands matching the text typed, best first.
+    fn update(&self) {
+        let query = self.entry.text();
+        let commands = self.commands.borrow();
+        let mut matches: Vec<(usize, FuzzyMatch)> = commands
+            .iter()
+            .enumerate()
+            .filter_map(|(index, command)| Some((index, fuzzy_match(&query, &command.title)?)))
+            .collect();
+        // Stable, so equally good matches keep their order
+        matches.sort_by_key(|(_, m)| std::cmp::Reverse(m.score));
+        matches.truncate(MAX_RESULTS);
+
+        while let Some(child) = self.results.first_child() {
+            self.results.remove(&child);
+        }
+        for (index, m) in &matches {
+            let command = &commands[*index];
+            let hbox = Box::builder()
+                .orientation(Orientation::Horizontal)
+                .spacing(12)
+                .build();
+            let title = Label::builder()
+                .label(command.title.as_str())
+                .halign(gtk::Align::Start)
+                .hexpand(true)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .build();
+            title.set_attributes(Some(&crate::search_highlight_attributes(&m.ranges)));
+            title.add_css_class("palette-title");
+            let hint = Label::new(Some(command.hint.as_str()));
+            hint.add_css_class("palette-hint");
+            hbox.append(&title);
+            hbox.append(&hint);
+            self.results.append(&hbox);
+        }
+        *self.shown.borrow_mut() = matches.into_iter().map(|(index, _)| index).collect();
+        self.select(0);
+    }
+
+    fn handle_key(&self, key: gdk::Key) -> glib::Propagation {
+        let count = self.shown.borrow().len();
+        if count == 0 {
+            return glib::Propagation::Proceed;
+        }
+        let selected = self
+            .results
+            .selected_row()
+            .map_or(0, |row| row.index() as usize);
+        match key {
+            gdk::Key::Down => self.select((selected + 1) % count),
+            gdk::Key::Up => self.select((selected + count - 1) % count),
+            _ => return glib::Propagation::Proceed,
+        }
+        glib::Propagation::Stop
+    }
+
+    fn select(&self, index: usize) {
+        let row = self.results.row_at_index(index as i32);
+        self.results.select_row(row.as_ref());
+        // Scroll the row into view, as the focus stays in the entry
+        if let Some(bounds) = row.and_then(|row| row.compute_bounds(&self.results)) {
+            let y = f64::from(bounds.y());
+            self.scrolled
+                .vadjustment()
+                .clamp_page(y, y + f64::from(bounds.height()));
+        }
+    }
+
+    /// Closes the palette and runs the shown command at `index`.
+    fn run(&self, index: usize) {
+        let Some(&command) = self.shown.borrow().get(index) else {
+            return;
+        };
+        self.window.close();
+        let commands = std::mem::take(&mut *self.commands.borrow_mut());
+        (commands[command].run)();
+    }
+}
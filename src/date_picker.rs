@@ -0,0 +1,198 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use glib::clone;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::{Box, Button, Calendar, Label, MenuButton, Orientation, Popover, SpinButton};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+type ChangedHandler = Rc<RefCell<Option<std::boxed::Box<dyn Fn(Option<NaiveDateTime>)>>>>;
+
+/// A button showing a due date, and optionally a time, that opens a calendar
+/// to pick them.
+pub struct DatePicker {
+    pub button: MenuButton,
+    calendar: Calendar,
+    hour: SpinButton,
+    minute: SpinButton,
+    value: Rc<Cell<Option<NaiveDateTime>>>,
+    with_time: bool,
+    changed: ChangedHandler,
+}
+
+impl DatePicker {
+    /// A picker for a date and time.
+    pub fn new() -> Self {
+        Self::build(true)
+    }
+
+    /// A picker for a day only; its value is at midnight.
+    pub fn date_only() -> Self {
+        Self::build(false)
+    }
+
+    fn build(with_time: bool) -> Self {
+        let calendar = Calendar::new();
+        calendar.add_css_class("calendar-widget");
+
+        let hour = SpinButton::with_range(0.0, 23.0, 1.0);
+        let minute = SpinButton::with_range(0.0, 59.0, 5.0);
+        for spin in [&hour, &minute] {
+            spin.set_wrap(true);
+            spin.set_width_chars(2);
+            // Show 09 rather than 9
+            spin.connect_output(|spin| {
+                spin.set_text(&format!("{:02}", spin.value() as u32));
+                glib::Propagation::Stop
+            });
+        }
+
+        let time_hbox = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(5)
+            .visible(with_time)
+            .build();
+        time_hbox.append(&Label::new(Some("Time")));
+        time_hbox.append(&hour);
+        time_hbox.append(&Label::new(Some(":")));
+        time_hbox.append(&minute);
+
+        let clear_button = Button::builder()
+            .label(if with_time { "No Due Date" } else { "Any Date" })
+            .build();
+        clear_button.add_css_class("action-button-small");
+        let set_button = Button::builder().label("Set").hexpand(true).build();
+        set_button.add_css_class("action-button");
+
+        let button_hbox = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(10)
+            .build();
+        button_hbox.append(&clear_button);
+        button_hbox.append(&set_button);
+
+        let vbox = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(10)
+            .build();
+        vbox.append(&calendar);
+        vbox.append(&time_hbox);
+        vbox.append(&button_hbox);
+
+        let popover = Popover::builder().child(&vbox).build();
+        popover.add_css_class("calendar-popover");
+
+        let button = MenuButton::builder().popover(&popover).build();
+        button.add_css_class("action-button-small");
+
+        let value: Rc<Cell<Option<NaiveDateTime>>> = Rc::default();
+        let changed: ChangedHandler = Rc::default();
+        set_button.connect_clicked(clone!(
+            #[weak]
+            button,
+            #[weak]
+            calendar,
+            #[weak]
+            hour,
+            #[weak]
+            minute,
+            #[strong]
+            value,
+            #[strong]
+            changed,
+            move |_| {
+                let date = calendar.date();
+                let time = if with_time {
+                    NaiveTime::from_hms_opt(hour.value() as u32, minute.value() as u32, 0)
+                } else {
+                    Some(NaiveTime::MIN)
+                };
+                let picked = NaiveDate::from_ymd_opt(
+                    date.year(),
+                    date.month() as u32,
+                    date.day_of_month() as u32,
+                )
+                .zip(time)
+                .map(|(date, time)| date.and_time(time));
+                value.set(picked);
+                button.set_label(&label_for(picked, with_time));
+                button.popdown();
+                if let Some(handler) = changed.borrow().as_ref() {
+                    handler(picked);
+                }
+            }
+        ));
+        clear_button.connect_clicked(clone!(
+            #[weak]
+            button,
+            #[strong]
+            value,
+            #[strong]
+            changed,
+            move |_| {
+                value.set(None);
+                button.set_label(&label_for(None, with_time));
+                button.popdown();
+                if let Some(handler) = changed.borrow().as_ref() {
+                    handler(None);
+                }
+            }
+        ));
+
+        let picker = DatePicker {
+            button,
+            calendar,
+            hour,
+            minute,
+            value,
+            with_time,
+            changed,
+        };
+        picker.set_value(None);
+        picker
+    }
+
+    pub fn value(&self) -> Option<NaiveDateTime> {
+        self.value.get()
+    }
+
+    /// Shows `value`, and opens the calendar at it (or at today when `None`).
+    /// Does not call the [`connect_changed`](Self::connect_changed) handler.
+    pub fn set_value(&self, value: Option<NaiveDateTime>) {
+        self.value.set(value);
+        self.button.set_label(&label_for(value, self.with_time));
+
+        let shown = match value {
+            Some(value) => glib::DateTime::from_local(
+                value.year(),
+                value.month() as i32,
+                value.day() as i32,
+                value.hour() as i32,
+                value.minute() as i32,
+                0.0,
+            )
+            .ok(),
+            None => glib::DateTime::now_local().ok(),
+        };
+        if let Some(shown) = shown {
+            self.calendar.select_day(&shown);
+        }
+        let (hour, minute) = value.map_or((9, 0), |v| (v.hour(), v.minute()));
+        self.hour.set_value(f64::from(hour));
+        self.minute.set_value(f64::from(minute));
+    }
+
+    /// Calls `handler` whenever the user sets or clears the value.
+    pub fn connect_changed(&self, handler: impl Fn(Option<NaiveDateTime>) + 'static) {
+        *self.changed.borrow_mut() = Some(std::boxed::Box::new(handler));
+    }
+}
+
+fn label_for(value: Option<NaiveDateTime>, with_time: bool) -> String {
+    match value {
+        Some(value) if with_time => value.format("%Y-%m-%d %H:%M").to_string(),
+        Some(value) => value.format("%Y-%m-%d").to_string(),
+        None if with_time => "No due date".to_string(),
+        None => "Any date".to_string(),
+    }
+}
@@ -0,0 +1,60 @@
+use gtk::prelude::*;
+use gtk::{Box, Button, Label, Orientation, Revealer, RevealerTransitionType};
+
+/// Non-modal strip shown above the columns when saving fails, offering to
+/// retry or to save somewhere else.
+pub struct ErrorBanner {
+    pub revealer: Revealer,
+    pub retry_button: Button,
+    pub save_as_button: Button,
+    label: Label,
+}
+
+impl ErrorBanner {
+    pub fn new() -> Self {
+        let hbox = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(10)
+            .build();
+        hbox.add_css_class("error-banner");
+
+        let label = Label::builder()
+            .halign(gtk::Align::Start)
+            .hexpand(true)
+            .wrap(true)
+            .xalign(0.0)
+            .build();
+        label.add_css_class("error-banner-label");
+
+        let retry_button = Button::builder().label("Retry").build();
+        retry_button.add_css_class("action-button-small");
+
+        let save_as_button = Button::builder().label("Save As…").build();
+        save_as_button.add_css_class("action-button-small");
+
+        hbox.append(&label);
+        hbox.append(&retry_button);
+        hbox.append(&save_as_button);
+
+        let revealer = Revealer::builder()
+            .transition_type(RevealerTransitionType::SlideDown)
+            .child(&hbox)
+            .build();
+
+        ErrorBanner {
+            revealer,
+            retry_button,
+            save_as_button,
+            label,
+        }
+    }
+
+    pub fn show(&self, message: &str) {
+        self.label.set_text(message);
+        self.revealer.set_reveal_child(true);
+    }
+
+    pub fn hide(&self) {
+        self.revealer.set_reveal_child(false);
+    }
+}
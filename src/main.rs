@@ -1,6 +1,6 @@
 use gio::ApplicationFlags;
 use glib::clone;
-use gtk::gdk::Display;
+use gtk::gdk::{self, Display};
 use gtk::glib;
 use gtk::prelude::*;
 use gtk::{
@@ -9,265 +9,778 @@ use gtk::{
 };
 use gtk::{CssProvider, style_context_add_provider_for_display};
 use std::cell::RefCell;
-use std::collections::HashSet;
-use std::fs;
-use std::io::{self, BufReader, BufWriter};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::rc::Rc;
 
-use serde::{Deserialize, Serialize};
+use chrono::{Local, NaiveTime};
 
-use uuid::Uuid;
+use todo_core::{
+    AppState, BoardError, BoardRegistry, Column, Config, DueFilter, MoveError, Priority,
+    STYLE_FILE_NAME, SavedFilter, SearchQuery, SortMode, StorageError, SyntaxError, TagMatch, Task,
+    Urgency, Workflow, WorkflowError, check_task_description, format_task_description,
+    parse_due_filter, parse_task_description, paths,
+};
 
-use chrono::{Local, NaiveDate, NaiveDateTime};
-use lazy_static::lazy_static;
-use regex::Regex;
+mod board_switcher;
+mod command_palette;
+mod date_picker;
+mod error_banner;
+mod notifier;
+mod quick_add;
+mod shortcuts;
+mod tag_filter;
+mod toast;
+
+use board_switcher::BoardSwitcher;
+use command_palette::{Command, CommandPalette};
+use date_picker::DatePicker;
+use error_banner::ErrorBanner;
+use notifier::Notifier;
+use quick_add::QuickAdd;
+use tag_filter::TagFilter;
+use toast::Toast;
 
-// --- Data Structures ---
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-enum TaskStatus {
-    Todo,
-    Doing,
-    Done,
-}
+// --- Main Application Function ---
+fn main() -> glib::ExitCode {
+    let app = Application::builder()
+        .application_id("com.example.RustGuiTodoApp")
+        .flags(ApplicationFlags::empty())
+        .build();
+
+    app.add_main_option(
+        "file",
+        glib::Char::from(b'f'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::Filename,
+        &format!(
+            "Task file to open (default: ${}, else the XDG data directory)",
+            paths::DATA_FILE_ENV
+        ),
+        Some("PATH"),
+    );
+
+    let file_override: Rc<RefCell<Option<PathBuf>>> = Rc::default();
+    app.connect_handle_local_options(clone!(
+        #[strong]
+        file_override,
+        move |_, options| {
+            *file_override.borrow_mut() = options.lookup::<PathBuf>("file").ok().flatten();
+            -1 // Carry on with the default handling
+        }
+    ));
+
+    app.connect_activate(move |app| {
+        // Launching again while running just raises the existing window
+        if let Some(window) = app.active_window() {
+            window.present();
+            return;
+        }
+        let config = Config::load().unwrap_or_else(|e| {
+            eprintln!("Error loading config: {}", e);
+            Config::default()
+        });
+        shortcuts::set_accels(app, &config.shortcuts);
+        let (app_state, boards, load_error) =
+            open_app_state(file_override.borrow_mut().take(), &config);
+        build_ui(
+            app,
+            Rc::new(RefCell::new(app_state)),
+            boards,
+            config.filters,
+            load_error.as_deref(),
+        );
+    });
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-enum Priority {
-    Low,
-    Medium,
-    High,
+    app.run()
 }
 
-impl Default for Priority {
-    fn default() -> Self {
-        Priority::Medium // Default priority for new tasks
+/// Locates and loads the task file, first copying over a `todo_data.json`
+/// left in the working directory by older versions. A load failure is
+/// returned as a message for the window to show.
+///
+/// Without an explicit file this opens the board used last; the board list
+/// is returned for the switcher.
+fn open_app_state(
+    explicit_file: Option<PathBuf>,
+    config: &Config,
+) -> (AppState, Option<BoardRegistry>, Option<String>) {
+    let (file_path, boards) = match paths::data_file_override(explicit_file) {
+        Some(file) => (file, None),
+        None => match BoardRegistry::load(&paths::data_dir_or_cwd()) {
+            Ok(boards) => (boards.board_path(boards.active_board()), Some(boards)),
+            Err(e) => {
+                eprintln!("Error loading boards: {}", e);
+                (paths::default_data_file(), None)
+            }
+        },
+    };
+    match paths::migrate_legacy_data_file(&file_path) {
+        Ok(Some(legacy)) => println!(
+            "Copied tasks from {} to {}",
+            legacy.display(),
+            file_path.display()
+        ),
+        Ok(None) => {}
+        Err(e) => eprintln!("Error migrating tasks: {}", e),
     }
+
+    let mut app_state = AppState::new(file_path);
+    app_state.backup_count = config.backup_count;
+    app_state.default_due_time = config.default_due_time;
+    app_state.reminder_minutes = config.reminders.clone();
+    app_state.snooze_minutes = config.snooze_minutes;
+    let load_error = app_state.load_tasks().err().map(|e| {
+        eprintln!("Error loading tasks: {}", e);
+        load_error_message(&app_state, &e)
+    });
+    (app_state, boards, load_error)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Task {
-    id: Uuid,
-    description: String,
-    status: TaskStatus,
-    category: Option<String>,
-    due_time: Option<NaiveDateTime>,
-    priority: Priority, // New field for priority
+/// Explains on the error banner why the board's file could not be loaded.
+fn load_error_message(app_state: &AppState, e: &StorageError) -> String {
+    let copy = match app_state.unreadable_copy() {
+        Some(copy) => format!(" A copy of it was kept as {}.", copy.display()),
+        None => String::new(),
+    };
+    format!(
+        "Could not load tasks: {}. Nothing is saved over the file until it loads; fix or move it, then retry.{}",
+        e, copy
+    )
 }
 
-struct AppState {
-    tasks: Vec<Task>,
-    file_path: PathBuf,
-    current_category_filter: Option<String>,
-    current_due_date_filter: Option<Option<NaiveDate>>,
+/// Applies the bundled stylesheet, then the user's `style.css` from the
+/// config directory on top of it if there is one.
+fn load_css() {
+    let display = Display::default().expect("Could not connect to a display.");
+
+    let provider = CssProvider::new();
+    provider.load_from_data(include_str!("../style.css"));
+    style_context_add_provider_for_display(
+        &display,
+        &provider,
+        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+    );
+
+    let user_css = paths::config_dir().map(|dir| dir.join(STYLE_FILE_NAME));
+    if let Some(user_css) = user_css.filter(|path| path.is_file()) {
+        let user_provider = CssProvider::new();
+        user_provider.load_from_path(&user_css);
+        style_context_add_provider_for_display(
+            &display,
+            &user_provider,
+            gtk::STYLE_PROVIDER_PRIORITY_USER,
+        );
+    }
 }
 
-impl AppState {
-    fn load_tasks(&mut self) -> Result<(), io::Error> {
-        if self.file_path.exists() {
-            let file = fs::File::open(&self.file_path)?;
-            let reader = BufReader::new(file);
-            match serde_json::from_reader::<_, Vec<Task>>(reader) {
-                Ok(loaded_tasks) => {
-                    self.tasks = loaded_tasks;
-                }
-                Err(e) => {
-                    eprintln!(
-                        "Warning: Failed to load tasks with new schema: {}. Attempting to load with old schema and assign default priority.",
-                        e
-                    );
-                    let file = fs::File::open(&self.file_path)?; // Re-open file
-                    let reader = BufReader::new(file);
-                    let old_tasks: Vec<OldTask> = serde_json::from_reader(reader)?;
-                    self.tasks = old_tasks
-                        .into_iter()
-                        .map(|old_task| Task {
-                            id: old_task.id,
-                            description: old_task.description,
-                            status: old_task.status,
-                            category: old_task.category,
-                            due_time: old_task.due_time,
-                            priority: Priority::Low, // Assign default priority
-                        })
-                        .collect();
-                }
+/// The widgets that show a board, plus the state they render.
+///
+/// Rows keep a weak reference to this so any handler can trigger a full
+/// refresh that honours the active filters.
+struct BoardView {
+    app_state: Rc<RefCell<AppState>>,
+    window: ApplicationWindow,
+    columns_hbox: Box,
+    /// One entry per column of the board, in order.
+    columns: RefCell<Vec<ColumnView>>,
+    tag_filter: TagFilter,
+    /// Picks the board's sort order; its ids are the modes' names.
+    sort_combo: ComboBoxText,
+    /// The Overdue, Today, Next 7 days and No date buttons.
+    smart_filters: Vec<(DueFilter, Button)>,
+    error_banner: ErrorBanner,
+    /// `None` when a single task file was given instead of using boards.
+    boards: Option<RefCell<BoardRegistry>>,
+    board_switcher: BoardSwitcher,
+    /// Offers to undo a delete.
+    toast: Toast,
+    undo_action: gio::SimpleAction,
+    redo_action: gio::SimpleAction,
+    undo_button: Button,
+    redo_button: Button,
+    /// Tasks whose checklist is shown, kept across refreshes.
+    expanded_tasks: RefCell<HashSet<uuid::Uuid>>,
+    /// The rows on screen, one per task the filter lets through.
+    task_rows: RefCell<HashMap<uuid::Uuid, TaskRow>>,
+    /// Sends reminders for the board's tasks.
+    notifier: Notifier,
+}
+
+impl BoardView {
+    /// Hides the error banner after a successful save, or shows why the last
+    /// save failed. The change itself stays in memory either way.
+    fn report<T>(&self, result: Result<T, StorageError>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.error_banner.hide();
+                Some(value)
             }
-        } else {
-            self.tasks = Vec::new();
+            Err(e) => {
+                eprintln!("Error saving tasks: {}", e);
+                self.error_banner.show(&format!(
+                    "Could not save tasks: {}. Your changes are kept until a save succeeds.",
+                    e
+                ));
+                None
+            }
+        }
+    }
+
+    /// Reads the board's file again after it failed to load.
+    fn reload(&self) {
+        let result = self.app_state.borrow_mut().load_tasks();
+        self.refresh();
+        match result {
+            Ok(()) => self.error_banner.hide(),
+            Err(e) => self.show_load_error(&e),
         }
-        Ok(())
     }
 
-    fn save_tasks(&self) -> Result<(), io::Error> {
-        let file = fs::File::create(&self.file_path)?;
-        let writer = BufWriter::new(file);
-        serde_json::to_writer_pretty(writer, &self.tasks)?;
-        Ok(())
+    fn show_load_error(&self, e: &StorageError) {
+        eprintln!("Error loading tasks: {}", e);
+        self.error_banner
+            .show(&load_error_message(&self.app_state.borrow(), e));
+    }
+
+    /// Shows why the board list could not be changed or saved.
+    fn report_board_error(&self, e: BoardError) {
+        eprintln!("Error updating boards: {}", e);
+        self.error_banner
+            .show(&format!("Could not update boards: {}.", e));
     }
 
-    fn add_task(&mut self, full_description: String) {
-        let (description, category, due_time, priority) = parse_task_description(&full_description);
-        let new_task = Task {
-            id: Uuid::new_v4(),
-            description,
-            status: TaskStatus::Todo,
-            category,
-            due_time,
-            priority: priority.unwrap_or_default(),
+    /// Saves the current board, then loads `name` in its place. Stays on the
+    /// current board if its changes cannot be saved, unless its file never
+    /// loaded and so is not saved anyway.
+    fn switch_board(self: &Rc<Self>, name: &str) {
+        let Some(boards) = &self.boards else {
+            return;
+        };
+        let unsaved = {
+            let app_state = self.app_state.borrow();
+            app_state.is_dirty() && !app_state.load_failed()
+        };
+        if unsaved {
+            let result = self.app_state.borrow_mut().save_tasks();
+            if self.report(result).is_none() {
+                self.board_switcher.set_boards(&boards.borrow());
+                return;
+            }
+        }
+
+        if let Err(e) = boards.borrow_mut().set_active(name) {
+            self.report_board_error(e);
+        }
+        let file_path = {
+            let boards = boards.borrow();
+            boards.board_path(boards.active_board())
         };
-        self.tasks.push(new_task);
-        self.save_tasks()
-            .expect("Failed to save tasks after adding");
+        let mut app_state = AppState::new(file_path);
+        {
+            // Tags differ between boards, the search and due date filter carry over
+            let current = self.app_state.borrow();
+            app_state.backup_count = current.backup_count;
+            app_state.default_due_time = current.default_due_time;
+            app_state.reminder_minutes = current.reminder_minutes.clone();
+            app_state.snooze_minutes = current.snooze_minutes;
+            app_state.filter.due_date = current.filter.due_date;
+            app_state.filter.search = current.filter.search.clone();
+        }
+        let load_error = app_state.load_tasks().err();
+        *self.app_state.borrow_mut() = app_state;
+
+        self.board_switcher.set_boards(&boards.borrow());
+        self.refresh();
+        match load_error {
+            Some(e) => self.show_load_error(&e),
+            None => self.error_banner.hide(),
+        }
+    }
+
+    /// Lets rows be dropped onto the list of the column `status`. A task
+    /// dropped on the top half of a row goes above it, on the bottom half
+    /// below it, and anywhere else at the bottom of the column.
+    fn add_drop_target(self: &Rc<Self>, status: String, list_box: &ListBox) {
+        let drop_target = gtk::DropTarget::new(String::static_type(), gdk::DragAction::MOVE);
+
+        drop_target.connect_motion(clone!(
+            #[weak]
+            list_box,
+            #[upgrade_or]
+            gdk::DragAction::empty(),
+            move |_, _, y| {
+                match list_box.row_at_y(y as i32) {
+                    Some(row) => list_box.drag_highlight_row(&row),
+                    None => list_box.drag_unhighlight_row(),
+                }
+                gdk::DragAction::MOVE
+            }
+        ));
+        drop_target.connect_leave(clone!(
+            #[weak]
+            list_box,
+            move |_| list_box.drag_unhighlight_row()
+        ));
+        drop_target.connect_drop(clone!(
+            #[weak(rename_to = view)]
+            self,
+            #[weak]
+            list_box,
+            #[upgrade_or]
+            false,
+            move |_, value, _, y| {
+                let Some(task_id) = value
+                    .get::<String>()
+                    .ok()
+                    .and_then(|id| uuid::Uuid::parse_str(&id).ok())
+                else {
+                    return false;
+                };
+                // Sorted columns put the task in its place themselves
+                let before = match view.app_state.borrow().sort {
+                    SortMode::Manual => view.task_below(&status, &list_box, y),
+                    _ => None,
+                };
+                view.move_task(task_id, &status, before, false);
+                true
+            }
+        ));
+        list_box.add_controller(drop_target);
+    }
+
+    /// Moves a task and refreshes the board. If a strict WIP limit refuses
+    /// the move, asks whether to move the task anyway.
+    fn move_task(
+        self: &Rc<Self>,
+        task_id: uuid::Uuid,
+        status: &str,
+        before: Option<uuid::Uuid>,
+        force: bool,
+    ) {
+        let result = self
+            .app_state
+            .borrow_mut()
+            .move_task(task_id, status, before, force);
+        match result {
+            Ok(()) => {
+                self.report(Ok(()));
+            }
+            Err(MoveError::Storage(e)) => {
+                self.report::<()>(Err(e));
+            }
+            Err(e @ MoveError::WipLimit { .. }) => {
+                confirm_over_limit(self, &e, task_id, status.to_string(), before)
+            }
+        }
+        self.refresh();
     }
 
-    fn update_task_status(&mut self, id: Uuid, new_status: TaskStatus) {
-        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
-            task.status = new_status;
-            self.save_tasks()
-                .expect("Failed to save tasks after status update");
+    /// The task a row dropped at height `y` of a column should go above, or
+    /// `None` for the bottom of the column.
+    fn task_below(&self, status: &str, list_box: &ListBox, y: f64) -> Option<uuid::Uuid> {
+        let row = list_box.row_at_y(y as i32)?;
+        let mut index = row.index() as usize;
+        if let Some(bounds) = row.compute_bounds(list_box)
+            && y > f64::from(bounds.y() + bounds.height() / 2.0)
+        {
+            index += 1;
+        }
+
+        // Rows are built from the filtered tasks, so the row index maps back
+        // to a task the same way.
+        self.app_state
+            .borrow()
+            .filtered_tasks()
+            .filter(|t| t.status == status)
+            .nth(index)
+            .map(|t| t.id)
+    }
+
+    /// Moves the keyboard focus to the "add item" entry of a task's
+    /// checklist, so items can be typed one after another across refreshes.
+    fn focus_checklist_entry(&self, task_id: uuid::Uuid) {
+        let entry = self
+            .task_rows
+            .borrow()
+            .get(&task_id)
+            .map(|task_row| task_row.checklist_entry.clone());
+        if let Some(entry) = entry {
+            entry.grab_focus();
         }
     }
 
-    fn update_task_description(&mut self, id: Uuid, new_description: String) {
-        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
-            task.description = new_description;
-            self.save_tasks()
-                .expect("Failed to save tasks after description update");
+    /// The row showing a task, if the filter lets it through.
+    fn row_for(&self, task_id: uuid::Uuid) -> Option<ListBoxRow> {
+        self.task_rows
+            .borrow()
+            .get(&task_id)
+            .map(|task_row| task_row.row.clone())
+    }
+
+    /// The row that has the keyboard focus, or one of whose buttons has it.
+    /// `None` while text is being typed, even into a row.
+    fn focused_row(&self) -> Option<ListBoxRow> {
+        let focus = self.window.focus()?;
+        let typing = focus
+            .downcast_ref::<gtk::Text>()
+            .is_some_and(|text| text.is_editable());
+        if typing {
+            return None;
         }
+        focus
+            .ancestor(ListBoxRow::static_type())?
+            .downcast::<ListBoxRow>()
+            .ok()
+    }
+
+    /// The task shown by the [`focused_row`](Self::focused_row).
+    fn focused_task(&self) -> Option<uuid::Uuid> {
+        let row = self.focused_row()?;
+        let list_box = row.parent()?;
+        let columns = self.columns.borrow();
+        let column = columns
+            .iter()
+            .find(|c| c.list_box.upcast_ref::<gtk::Widget>() == &list_box)?;
+        let app_state = self.app_state.borrow();
+        let task = app_state
+            .filtered_tasks()
+            .filter(|t| t.status == column.column.id)
+            .nth(row.index() as usize)?;
+        Some(task.id)
     }
 
-    fn delete_task(&mut self, id: Uuid) {
-        self.tasks.retain(|t| t.id != id);
-        self.save_tasks()
-            .expect("Failed to save tasks after deletion");
+    fn focus_task(&self, task_id: uuid::Uuid) {
+        if let Some(row) = self.row_for(task_id) {
+            row.grab_focus();
+        }
     }
 
-    fn get_unique_categories(&self) -> Vec<String> {
-        let mut categories = HashSet::new();
-        for task in &self.tasks {
-            if let Some(cat) = &task.category {
-                categories.insert(cat.clone());
+    /// Enables the actions on the focused task, such as `delete-task`, only
+    /// while a task row has the focus and is not being edited, so their
+    /// shortcuts never take keys from a text field.
+    fn update_task_actions(&self) {
+        let enabled = self.focused_task().is_some();
+        let Some(app) = self.window.application() else {
+            return;
+        };
+        for name in TASK_ACTIONS {
+            if let Some(action) = app.lookup_action(name).and_downcast::<gio::SimpleAction>() {
+                action.set_enabled(enabled);
             }
         }
-        let mut sorted_categories: Vec<String> = categories.into_iter().collect();
-        sorted_categories.sort();
-        sorted_categories
     }
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct OldTask {
-    id: Uuid,
-    description: String,
-    status: TaskStatus,
-    category: Option<String>,
-    due_time: Option<NaiveDateTime>,
-}
+    /// Puts a task's row into edit mode, as double-clicking it does.
+    fn edit_task(&self, task_id: uuid::Uuid) {
+        let Some(text) = self
+            .app_state
+            .borrow()
+            .find_task(task_id)
+            .map(format_task_description)
+        else {
+            return;
+        };
+        let entry = self
+            .task_rows
+            .borrow()
+            .get(&task_id)
+            .map(|task_row| task_row.entry.clone());
+        if let Some(entry) = entry {
+            start_task_edit(self, &entry, &text);
+        }
+    }
 
-fn parse_task_description(
-    description: &str,
-) -> (
-    String,
-    Option<String>,
-    Option<NaiveDateTime>,
-    Option<Priority>,
-) {
-    lazy_static! {
-        static ref CATEGORY_RE: Regex = Regex::new(r"(?i)#([a-zA-Z0-9_]+)").unwrap();
-        static ref TIME_RE: Regex = Regex::new(r"#(\d{4}-\d{2}-\d{2}_\d{2}:\d{2})").unwrap();
-        static ref PRIORITY_RE: Regex = Regex::new(r"(?i)#(p[1-3]|high|medium|low)").unwrap();
-    }
-
-    let mut remaining_description = description.to_string();
-    let mut category: Option<String> = None;
-    let mut due_time: Option<NaiveDateTime> = None;
-    let mut priority: Option<Priority> = None;
-
-    // Extract priority
-    if let Some(captures) = PRIORITY_RE.captures(&remaining_description) {
-        if let Some(p_match) = captures.get(1) {
-            priority = match p_match.as_str().to_lowercase().as_str() {
-                "p1" | "high" => Some(Priority::High),
-                "p2" | "medium" => Some(Priority::Medium),
-                "p3" | "low" => Some(Priority::Low),
-                _ => None,
-            };
-            remaining_description = PRIORITY_RE
-                .replace_all(&remaining_description, "")
-                .to_string();
+    fn set_task_priority(self: &Rc<Self>, task_id: uuid::Uuid, priority: Priority) {
+        let Some(task) = self.app_state.borrow().find_task(task_id).cloned() else {
+            return;
+        };
+        let result = self
+            .app_state
+            .borrow_mut()
+            .update_task(Task { priority, ..task });
+        self.report(result);
+        self.refresh();
+    }
+
+    /// Moves a task to the column before (`step` -1) or after (1) its own,
+    /// keeping the keyboard focus on it.
+    fn move_task_by(self: &Rc<Self>, task_id: uuid::Uuid, step: isize) {
+        let target = {
+            let app_state = self.app_state.borrow();
+            let columns = &app_state.workflow.columns;
+            app_state
+                .find_task(task_id)
+                .and_then(|task| columns.iter().position(|c| c.id == task.status))
+                .and_then(|index| index.checked_add_signed(step))
+                .and_then(|index| columns.get(index))
+                .map(|column| column.id.clone())
+        };
+        if let Some(target) = target {
+            self.move_task(task_id, &target, None, false);
+            self.focus_task(task_id);
         }
     }
 
-    // Extract category
-    if let Some(captures) = CATEGORY_RE.captures(&remaining_description) {
-        if let Some(cat_match) = captures.get(1) {
-            let cat_str = cat_match.as_str().to_lowercase();
-            if !matches!(
-                cat_str.as_str(),
-                "p1" | "p2" | "p3" | "high" | "medium" | "low"
-            ) {
-                category = Some(cat_str);
-                remaining_description = CATEGORY_RE
-                    .replace_all(&remaining_description, "")
-                    .to_string();
+    /// Deletes a task straight away and offers to undo.
+    fn delete_task(self: &Rc<Self>, task_id: uuid::Uuid) {
+        let result = self.app_state.borrow_mut().delete_task(task_id);
+        self.report(result);
+        self.refresh();
+        self.toast.show("Task deleted");
+    }
+
+    /// Moves the keyboard focus between rows the Vim way: `j` and `k` within
+    /// a column, `h` and `l` to the columns beside it, `g` and `G` to the
+    /// first and last row.
+    fn navigate(
+        &self,
+        list_box: &ListBox,
+        key: gdk::Key,
+        modifiers: gdk::ModifierType,
+    ) -> glib::Propagation {
+        if modifiers.intersects(gdk::ModifierType::CONTROL_MASK | gdk::ModifierType::ALT_MASK) {
+            return glib::Propagation::Proceed;
+        }
+        let Some(row) = self
+            .focused_row()
+            .filter(|row| row.parent().as_ref() == Some(list_box.upcast_ref::<gtk::Widget>()))
+        else {
+            return glib::Propagation::Proceed;
+        };
+        let index = row.index();
+        let last_index = |list_box: &ListBox| {
+            (0..)
+                .take_while(|&i| list_box.row_at_index(i).is_some())
+                .last()
+        };
+        let target = match key {
+            gdk::Key::j => list_box.row_at_index(index + 1),
+            gdk::Key::k if index > 0 => list_box.row_at_index(index - 1),
+            gdk::Key::k => None,
+            gdk::Key::g => list_box.row_at_index(0),
+            gdk::Key::G => last_index(list_box).and_then(|i| list_box.row_at_index(i)),
+            gdk::Key::h | gdk::Key::l => {
+                let columns = self.columns.borrow();
+                let position = columns.iter().position(|c| &c.list_box == list_box);
+                let Some(position) = position else {
+                    return glib::Propagation::Proceed;
+                };
+                let others: Vec<&ColumnView> = if key == gdk::Key::h {
+                    columns[..position].iter().rev().collect()
+                } else {
+                    columns[position + 1..].iter().collect()
+                };
+                // Skip empty columns, staying as near the same height as possible
+                others.into_iter().find_map(|column| {
+                    let last = last_index(&column.list_box)?;
+                    column.list_box.row_at_index(index.min(last))
+                })
             }
+            _ => return glib::Propagation::Proceed,
+        };
+        if let Some(target) = target {
+            target.grab_focus();
         }
+        glib::Propagation::Stop
     }
 
-    // Extract time
-    if let Some(captures) = TIME_RE.captures(&remaining_description) {
-        if let Some(time_str_match) = captures.get(1) {
-            if let Ok(dt) = NaiveDateTime::parse_from_str(time_str_match.as_str(), "%Y-%m-%d_%H:%M")
-            {
-                due_time = Some(dt);
-                remaining_description = TIME_RE.replace_all(&remaining_description, "").to_string();
+    /// Recreates the columns on screen if the board's columns were changed,
+    /// reordered or renamed since they were last built.
+    fn sync_columns(self: &Rc<Self>) {
+        let workflow = self.app_state.borrow().workflow.clone();
+        let mut columns = self.columns.borrow_mut();
+        if columns
+            .iter()
+            .map(|c| &c.column)
+            .eq(workflow.columns.iter())
+        {
+            return;
+        }
+
+        for column in columns.drain(..) {
+            self.columns_hbox.remove(&column.container);
+        }
+        for column in workflow.columns {
+            let list_box = ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .build();
+            list_box.add_css_class("task-list-box");
+            let title_label = Label::new(None);
+            let container = create_task_column(&title_label, &list_box);
+            self.add_drop_target(column.id.clone(), &list_box);
+            let keys = gtk::EventControllerKey::new();
+            keys.connect_key_pressed(clone!(
+                #[weak(rename_to = view)]
+                self,
+                #[weak]
+                list_box,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |_, key, _, modifiers| view.navigate(&list_box, key, modifiers)
+            ));
+            list_box.add_controller(keys);
+            self.columns_hbox.append(&container);
+            columns.push(ColumnView {
+                column,
+                container,
+                title_label,
+                list_box,
+            });
+        }
+    }
+
+    fn list_box_for(&self, status: &str) -> Option<ListBox> {
+        self.columns
+            .borrow()
+            .iter()
+            .find(|c| c.column.id == status)
+            .map(|c| c.list_box.clone())
+    }
+
+    /// Enables undo and redo only when there is something to undo or redo,
+    /// and names it in the button tooltips.
+    fn update_history_actions(&self) {
+        let app_state = self.app_state.borrow();
+        let undo = app_state.undo_label();
+        let redo = app_state.redo_label();
+        self.undo_action.set_enabled(undo.is_some());
+        self.redo_action.set_enabled(redo.is_some());
+        self.undo_button
+            .set_tooltip_text(Some(&format!("Undo {} (Ctrl+Z)", undo.unwrap_or(""))));
+        self.redo_button
+            .set_tooltip_text(Some(&format!("Redo {} (Ctrl+Shift+Z)", redo.unwrap_or(""))));
+    }
+
+    /// Undoes (`redo == false`) or redoes the latest change.
+    fn step_history(self: &Rc<Self>, redo: bool) {
+        self.toast.hide();
+        let result = if redo {
+            self.app_state.borrow_mut().redo()
+        } else {
+            self.app_state.borrow_mut().undo()
+        };
+        self.report(result);
+        self.refresh();
+    }
+
+    /// Selects or deselects `tag` in the tag filter and shows the result.
+    fn toggle_tag_filter(self: &Rc<Self>, tag: &str, selected: bool) {
+        {
+            let mut app_state = self.app_state.borrow_mut();
+            let tags = &mut app_state.filter.tags;
+            let present = tags.iter().any(|t| t == tag);
+            if present == selected {
+                return;
+            }
+            if selected {
+                tags.push(tag.to_string());
+            } else {
+                tags.retain(|t| t != tag);
             }
         }
+        self.refresh();
     }
 
-    (
-        remaining_description.trim().to_string(),
-        category,
-        due_time,
-        priority,
-    )
-}
+    /// Rebuilds the tag filter and every column from `app_state`.
+    fn refresh(self: &Rc<Self>) {
+        self.sync_columns();
+        self.update_history_actions();
 
-// --- Main Application Function ---
-fn main() {
-    let data_file_path = PathBuf::from("todo_data.json");
-    let app_state = Rc::new(RefCell::new(AppState {
-        tasks: Vec::new(),
-        file_path: data_file_path,
-        current_category_filter: None,
-        current_due_date_filter: None,
-    }));
-
-    if let Err(e) = app_state.borrow_mut().load_tasks() {
-        eprintln!("Error loading tasks: {}", e);
+        // Clear all list boxes
+        self.task_rows.borrow_mut().clear();
+        for column in self.columns.borrow().iter() {
+            while let Some(child) = column.list_box.first_child() {
+                column.list_box.remove(&child);
+            }
+        }
+
+        // Syncing the filter bar fires its change handlers, which borrow
+        // `app_state` mutably, so it must not be borrowed while that happens
+        let (tags, filter, sort) = {
+            let app_state = self.app_state.borrow();
+            (
+                app_state.all_tags(),
+                app_state.filter.clone(),
+                app_state.sort,
+            )
+        };
+        self.tag_filter.set_tags(&tags, &filter);
+        self.sort_combo.set_active_id(Some(&sort.to_string()));
+
+        let app_state = self.app_state.borrow();
+        for (filter, button) in &self.smart_filters {
+            if app_state.filter.due_date == Some(*filter) {
+                button.add_css_class("smart-filter-active");
+            } else {
+                button.remove_css_class("smart-filter-active");
+            }
+        }
+
+        for column in self.columns.borrow().iter() {
+            column.update_title(app_state.count_in_column(&column.column.id));
+        }
+
+        // Re-populate lists, applying filters
+        for task in app_state.filtered_tasks() {
+            if let Some(list_box) = self.list_box_for(&task.status) {
+                list_box.append(&create_task_row(task, &app_state.workflow, self));
+            }
+        }
     }
+}
 
-    let app = Application::builder()
-        .application_id("com.example.RustGuiTodoApp")
-        .flags(ApplicationFlags::empty())
-        .build();
+/// The widgets of a task's row that are reached for after it is built.
+struct TaskRow {
+    row: ListBoxRow,
+    /// Shows the task and turns into its editor.
+    entry: Entry,
+    /// Adds items to the task's checklist.
+    checklist_entry: Entry,
+}
 
-    let app_state_clone = Rc::clone(&app_state);
-    app.connect_activate(move |app| {
-        build_ui(app, Rc::clone(&app_state_clone));
-    });
+/// A column on screen and the board column it shows.
+struct ColumnView {
+    column: Column,
+    container: Box,
+    title_label: Label,
+    list_box: ListBox,
+}
 
-    app.run();
+impl ColumnView {
+    /// Shows the column's name with a badge counting its tasks against the
+    /// WIP limit, styled as a warning once the limit is exceeded.
+    fn update_title(&self, count: usize) {
+        let badge = match self.column.wip_limit {
+            Some(limit) => format!("{}/{}", count, limit),
+            None => count.to_string(),
+        };
+        self.title_label.set_markup(&format!(
+            "{} <span size=\"smaller\" alpha=\"70%\">{}</span>",
+            glib::markup_escape_text(&self.column.name),
+            badge
+        ));
+
+        if self.column.over_limit(count) {
+            self.container.add_css_class("wip-exceeded");
+            self.title_label
+                .set_tooltip_text(Some("Over the work-in-progress limit"));
+        } else {
+            self.container.remove_css_class("wip-exceeded");
+            self.title_label.set_tooltip_text(None);
+        }
+    }
 }
 
 // --- UI Building Function ---
-fn build_ui(app: &Application, app_state: Rc<RefCell<AppState>>) {
-    let provider = CssProvider::new();
-    provider.load_from_path("style.css");
-    style_context_add_provider_for_display(
-        &Display::default().expect("Could not connect to a display."),
-        &provider,
-        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
-    );
+fn build_ui(
+    app: &Application,
+    app_state: Rc<RefCell<AppState>>,
+    boards: Option<BoardRegistry>,
+    saved_filters: BTreeMap<String, SavedFilter>,
+    load_error: Option<&str>,
+) {
+    load_css();
 
     let window = ApplicationWindow::builder()
         .application(app)
@@ -277,8 +790,6 @@ fn build_ui(app: &Application, app_state: Rc<RefCell<AppState>>) {
         .resizable(true)
         .build();
 
-    let window_rc = Rc::new(window.clone());
-
     let main_vbox = Box::builder()
         .orientation(Orientation::Vertical)
         .spacing(10)
@@ -305,6 +816,49 @@ fn build_ui(app: &Application, app_state: Rc<RefCell<AppState>>) {
     title_label.add_css_class("title-label");
     header_hbox.append(&title_label);
 
+    // Board switcher, hidden when a single task file was given
+    let board_switcher = BoardSwitcher::new();
+    match &boards {
+        Some(boards) => board_switcher.set_boards(boards),
+        None => board_switcher.container.set_visible(false),
+    }
+    header_hbox.append(&board_switcher.container);
+
+    // Undo and Redo Buttons, enabled by `refresh`
+    let undo_button = Button::builder()
+        .label("Undo")
+        .valign(gtk::Align::Center)
+        .action_name("win.undo")
+        .build();
+    undo_button.add_css_class("action-button-small");
+    header_hbox.append(&undo_button);
+
+    let redo_button = Button::builder()
+        .label("Redo")
+        .valign(gtk::Align::Center)
+        .action_name("win.redo")
+        .build();
+    redo_button.add_css_class("action-button-small");
+    header_hbox.append(&redo_button);
+
+    // Columns Button
+    let columns_button = Button::builder()
+        .label("Columns…")
+        .valign(gtk::Align::Center)
+        .tooltip_text("Add, rename and reorder this board's columns")
+        .build();
+    columns_button.add_css_class("action-button-small");
+    header_hbox.append(&columns_button);
+
+    // Restore Button
+    let restore_button = Button::builder()
+        .label("Restore…")
+        .valign(gtk::Align::Center)
+        .tooltip_text("Restore tasks from a backup")
+        .build();
+    restore_button.add_css_class("action-button-small");
+    header_hbox.append(&restore_button);
+
     // Clock Label
     let clock_label = Label::builder()
         .halign(gtk::Align::End)
@@ -314,13 +868,6 @@ fn build_ui(app: &Application, app_state: Rc<RefCell<AppState>>) {
     header_hbox.append(&clock_label);
     main_vbox.append(&header_hbox);
 
-    let clock_label_clone = clock_label.clone();
-    glib::timeout_add_seconds_local(1, move || {
-        let now = Local::now();
-        clock_label_clone.set_text(&now.format("%I:%M:%S %p").to_string());
-        glib::ControlFlow::Continue
-    });
-
     // Input area for new tasks
     let input_hbox = Box::builder()
         .orientation(Orientation::Horizontal)
@@ -329,17 +876,29 @@ fn build_ui(app: &Application, app_state: Rc<RefCell<AppState>>) {
     input_hbox.add_css_class("input-area");
 
     let entry = Entry::builder()
-        .placeholder_text("Enter a new task (e.g., Buy milk #home #P2 #2025-07-05_10:00)...")
+        .placeholder_text("Enter a new task (e.g., Buy milk #home #P2 #tomorrow_10am)...")
+        .tooltip_text("Type # for tags, priorities, due dates and repeat rules")
         .hexpand(true)
         .build();
     entry.add_css_class("task-entry");
+    let quick_add = QuickAdd::new(&entry, &app_state);
+
+    let due_picker = Rc::new(DatePicker::new());
+    due_picker
+        .button
+        .set_tooltip_text(Some("Due date of the new task"));
 
     let add_button = Button::builder().label("Add Task").build();
     add_button.add_css_class("action-button");
 
     input_hbox.append(&entry);
+    input_hbox.append(&due_picker.button);
     input_hbox.append(&add_button);
     main_vbox.append(&input_hbox);
+    main_vbox.append(&quick_add.preview);
+
+    let entry_error_label = field_error_label();
+    main_vbox.append(&entry_error_label);
 
     // Filter area
     let filter_hbox = Box::builder()
@@ -349,234 +908,1673 @@ fn build_ui(app: &Application, app_state: Rc<RefCell<AppState>>) {
     filter_hbox.add_css_class("input-area");
     filter_hbox.add_css_class("filter-hbox");
 
-    // Category Filter ComboBoxText
-    let category_filter_combo = ComboBoxText::new();
-    category_filter_combo.append_text("All Categories");
-    category_filter_combo.set_active_id(Some("All Categories"));
-    category_filter_combo.set_hexpand(true);
-    category_filter_combo.add_css_class("filter-combo");
+    // Search box, filtering as you type
+    let search_entry = gtk::SearchEntry::builder()
+        .placeholder_text("Search (Ctrl+F)")
+        .tooltip_text("Words or \"quoted phrases\" to look for; -word leaves out tasks with it")
+        .hexpand(true)
+        .build();
+
+    // Tag filter, applied as soon as a tag is ticked
+    let tag_filter = TagFilter::new();
+
+    let sort_combo = ComboBoxText::new();
+    for mode in SortMode::ALL {
+        sort_combo.append(Some(&mode.to_string()), &format!("Sort: {}", mode.label()));
+    }
+    sort_combo.set_tooltip_text(Some(
+        "Order of the tasks in each column, kept for the board",
+    ));
+    sort_combo.add_css_class("filter-combo");
 
     let due_date_filter_entry = Entry::builder()
-        .placeholder_text("Filter by due date (YYYY-MM-DD)")
+        .placeholder_text("Filter by due date (YYYY-MM-DD, none, overdue, today or week)")
         .hexpand(true)
         .build();
     due_date_filter_entry.add_css_class("task-entry");
 
+    let due_filter_picker = Rc::new(DatePicker::date_only());
+    due_filter_picker
+        .button
+        .set_tooltip_text(Some("Pick the due date to filter by"));
+
     let apply_filter_button = Button::builder().label("Apply Filters").build();
     apply_filter_button.add_css_class("action-button");
 
     let clear_filters_button = Button::builder().label("Clear Filters").build();
     clear_filters_button.add_css_class("action-button-small");
 
-    filter_hbox.append(&category_filter_combo);
+    filter_hbox.append(&search_entry);
+    filter_hbox.append(&tag_filter.button);
+    filter_hbox.append(&sort_combo);
     filter_hbox.append(&due_date_filter_entry);
+    filter_hbox.append(&due_filter_picker.button);
     filter_hbox.append(&apply_filter_button);
     filter_hbox.append(&clear_filters_button);
     main_vbox.append(&filter_hbox);
 
-    // Horizontal box for the three columns (Todo, Doing, Done)
+    // Smart due date filters, a shortcut for typing their names
+    let smart_filters_hbox = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(5)
+        .build();
+    smart_filters_hbox.add_css_class("smart-filters");
+    let smart_filters: Vec<(DueFilter, Button)> = [
+        (DueFilter::Overdue, "Overdue"),
+        (DueFilter::Today, "Today"),
+        (DueFilter::Next7Days, "Next 7 days"),
+        (DueFilter::NoDate, "No date"),
+    ]
+    .into_iter()
+    .map(|(filter, label)| {
+        let button = Button::builder().label(label).build();
+        button.add_css_class("smart-filter-button");
+        smart_filters_hbox.append(&button);
+        (filter, button)
+    })
+    .collect();
+    main_vbox.append(&smart_filters_hbox);
+
+    let filter_error_label = field_error_label();
+    main_vbox.append(&filter_error_label);
+
+    // Horizontal box for the board's columns, filled in by `refresh`
     let columns_hbox = Box::builder()
         .orientation(Orientation::Horizontal)
         .spacing(20)
         .vexpand(true)
         .build();
 
-    let todo_list_box_rc = Rc::new(
-        ListBox::builder()
-            .selection_mode(gtk::SelectionMode::None)
-            .build(),
-    );
-    todo_list_box_rc.add_css_class("task-list-box");
-
-    let doing_list_box_rc = Rc::new(
-        ListBox::builder()
-            .selection_mode(gtk::SelectionMode::None)
-            .build(),
-    );
-    doing_list_box_rc.add_css_class("task-list-box");
-
-    let done_list_box_rc = Rc::new(
-        ListBox::builder()
-            .selection_mode(gtk::SelectionMode::None)
-            .build(),
-    );
-    done_list_box_rc.add_css_class("task-list-box");
-
-    let refresh_ui = Rc::new(
-        glib::clone!(@strong todo_list_box_rc, @strong doing_list_box_rc, @strong done_list_box_rc, @strong window_rc, @strong category_filter_combo => move |app_state: Rc<RefCell<AppState>>| {
-            // Clear all list boxes
-            while let Some(child) = todo_list_box_rc.first_child() {
-                todo_list_box_rc.remove(&child);
-            }
-            while let Some(child) = doing_list_box_rc.first_child() {
-                doing_list_box_rc.remove(&child);
-            }
-            while let Some(child) = done_list_box_rc.first_child() {
-                done_list_box_rc.remove(&child);
-            }
+    let undo_action = gio::SimpleAction::new("undo", None);
+    let redo_action = gio::SimpleAction::new("redo", None);
+    window.add_action(&undo_action);
+    window.add_action(&redo_action);
+
+    let find_action = gio::SimpleAction::new("find", None);
+    find_action.connect_activate(clone!(
+        #[weak]
+        search_entry,
+        move |_, _| {
+            search_entry.grab_focus();
+        }
+    ));
+    window.add_action(&find_action);
+
+    let view = Rc::new(BoardView {
+        app_state,
+        window: window.clone(),
+        columns_hbox: columns_hbox.clone(),
+        columns: RefCell::default(),
+        tag_filter,
+        sort_combo,
+        smart_filters,
+        error_banner: ErrorBanner::new(),
+        boards: boards.map(RefCell::new),
+        board_switcher,
+        toast: Toast::new("Undo"),
+        undo_action,
+        redo_action,
+        undo_button,
+        redo_button,
+        expanded_tasks: RefCell::default(),
+        task_rows: RefCell::default(),
+        notifier: Notifier::new(app),
+    });
 
-            // Populate category filter combo box
-            category_filter_combo.remove_all();
-            category_filter_combo.append_text("All Categories"); // Always present
-            let unique_categories = app_state.borrow().get_unique_categories();
-            for cat in unique_categories {
-                category_filter_combo.append_text(&cat);
-            }
-            // Set active filter if any, otherwise default to "All Categories"
-            if let Some(current_filter) = &app_state.borrow().current_category_filter {
-                category_filter_combo.set_active_id(Some(current_filter));
-            } else {
-                category_filter_combo.set_active_id(Some("All Categories"));
+    // Tick the clock and send reminders that have come up
+    glib::timeout_add_seconds_local(
+        1,
+        clone!(
+            #[weak]
+            view,
+            #[upgrade_or]
+            glib::ControlFlow::Break,
+            move || {
+                let now = Local::now();
+                clock_label.set_text(&now.format("%I:%M:%S %p").to_string());
+                view.notifier.check(&view.app_state.borrow());
+                glib::ControlFlow::Continue
             }
+        ),
+    );
 
-
-            // Iterate through tasks and re-populate lists, applying filters
-            for task in app_state.borrow().tasks.iter() {
-                let app_state_borrowed = app_state.borrow();
-                let mut matches_filter = true;
-
-                // Category filter
-                if let Some(filter_cat) = &app_state_borrowed.current_category_filter {
-                    if let Some(task_cat) = &task.category {
-                        if !task_cat.contains(filter_cat) {
-                            matches_filter = false;
-                        }
-                    } else {
-                        // Task has no category, but filter is applied
-                        matches_filter = false;
-                    }
-                }
-
-                // Due date filter
-                if let Some(filter_date_option) = &app_state_borrowed.current_due_date_filter {
-                    match filter_date_option {
-                        Some(filter_date) => { // Filtering for a specific date
-                            if let Some(task_due_time) = &task.due_time {
-                                if task_due_time.date() != *filter_date {
-                                    matches_filter = false;
-                                }
-                            } else {
-                                // Task has no due date, but a date filter is applied
-                                matches_filter = false;
-                            }
-                        }
-                        None => { // Filtering for tasks *without* a due date
-                            if task.due_time.is_some() {
-                                matches_filter = false;
-                            }
-                        }
-                    }
-                }
-
-
-                if matches_filter {
-                    let task_row = create_task_row(
-                        task.clone(),
-                        Rc::clone(&app_state),
-                        Rc::clone(&todo_list_box_rc),
-                        Rc::clone(&doing_list_box_rc),
-                        Rc::clone(&done_list_box_rc),
-                        Rc::clone(&window_rc)
-                    );
-                    match task.status {
-                        TaskStatus::Todo => todo_list_box_rc.append(&task_row),
-                        TaskStatus::Doing => doing_list_box_rc.append(&task_row),
-                        TaskStatus::Done => done_list_box_rc.append(&task_row),
-                    }
-                }
+    // The buttons on reminder notifications
+    let snooze_action =
+        gio::SimpleAction::new(notifier::SNOOZE_ACTION, Some(glib::VariantTy::STRING));
+    snooze_action.connect_activate(clone!(
+        #[weak]
+        view,
+        move |_, parameter| {
+            let Some(task_id) = notification_task(parameter) else {
+                return;
+            };
+            let now = Local::now().naive_local();
+            let result = view.app_state.borrow_mut().snooze_reminder(task_id, now);
+            view.report(result);
+            view.notifier.withdraw(task_id);
+        }
+    ));
+    app.add_action(&snooze_action);
+
+    let complete_action =
+        gio::SimpleAction::new(notifier::COMPLETE_ACTION, Some(glib::VariantTy::STRING));
+    complete_action.connect_activate(clone!(
+        #[weak]
+        view,
+        move |_, parameter| {
+            let Some(task_id) = notification_task(parameter) else {
+                return;
+            };
+            let done = view
+                .app_state
+                .borrow()
+                .workflow
+                .first_completed()
+                .map(|column| column.id.clone());
+            if let Some(done) = done {
+                view.move_task(task_id, &done, None, false);
             }
-        }),
-    );
+            view.notifier.withdraw(task_id);
+        }
+    ));
+    app.add_action(&complete_action);
 
-    columns_hbox.append(&create_task_column("TO DO", &todo_list_box_rc));
-    columns_hbox.append(&create_task_column("DOING", &doing_list_box_rc));
-    columns_hbox.append(&create_task_column("DONE", &done_list_box_rc));
+    main_vbox.append(&view.error_banner.revealer);
 
-    main_vbox.append(&columns_hbox);
+    // The toast floats over the bottom of the columns
+    let board_overlay = gtk::Overlay::builder().child(&columns_hbox).build();
+    view.toast.action_button.set_action_name(Some("win.undo"));
+    board_overlay.add_overlay(&view.toast.revealer);
+    main_vbox.append(&board_overlay);
 
     window.set_child(Some(&main_vbox));
+    window.set_help_overlay(Some(&shortcuts::shortcuts_window(app)));
+    add_keyboard_actions(app, &view, &entry, &clear_filters_button);
     window.present();
 
     // Initial UI refresh
-    refresh_ui(Rc::clone(&app_state));
-
-    // Add Task button handler
-    add_button.connect_clicked(
-        glib::clone!(@weak entry, @strong app_state, @strong refresh_ui => move |_| {
-            let description = entry.text().to_string();
-            if !description.is_empty() {
-                app_state.borrow_mut().add_task(description);
-                entry.set_text(""); // Clear the input field
-                refresh_ui(Rc::clone(&app_state));
-            }
-        }),
-    );
-
-    // Apply Filter button handler
-    apply_filter_button.connect_clicked(
-        glib::clone!(@weak category_filter_combo, @weak due_date_filter_entry, @strong app_state, @strong refresh_ui => move |_| {
-            let selected_category = category_filter_combo.active_text().map(|t| t.to_string());
-            let date_text = due_date_filter_entry.text().to_string();
+    view.refresh();
+    if let Some(message) = load_error {
+        view.error_banner.show(message);
+    }
 
-            let mut app_state_mut = app_state.borrow_mut();
+    // Undo and redo handlers
+    view.undo_action.connect_activate(clone!(
+        #[weak]
+        view,
+        move |_, _| view.step_history(false)
+    ));
+    view.redo_action.connect_activate(clone!(
+        #[weak]
+        view,
+        move |_, _| view.step_history(true)
+    ));
 
-            // Update category filter
-            if let Some(category_string) = selected_category {
-                if category_string == "All Categories" {
-                    app_state_mut.current_category_filter = None;
-                } else {
-                    app_state_mut.current_category_filter = Some(category_string.to_lowercase());
-                }
+    // Error banner handlers
+    view.error_banner.retry_button.connect_clicked(clone!(
+        #[weak]
+        view,
+        move |_| {
+            if view.app_state.borrow().load_failed() {
+                view.reload();
             } else {
-                app_state_mut.current_category_filter = None; // No category selected
+                let result = view.app_state.borrow_mut().save_tasks();
+                view.report(result);
             }
+        }
+    ));
+    view.error_banner.save_as_button.connect_clicked(clone!(
+        #[weak]
+        view,
+        move |_| show_save_as_dialog(&view)
+    ));
 
+    // Keep unsaved changes from being lost silently on close
+    window.connect_close_request(clone!(
+        #[weak]
+        view,
+        #[upgrade_or]
+        glib::Propagation::Proceed,
+        move |window| {
+            if !view.app_state.borrow().is_dirty() {
+                return glib::Propagation::Proceed;
+            }
+            let result = view.app_state.borrow_mut().save_tasks();
+            if view.report(result).is_some() {
+                return glib::Propagation::Proceed;
+            }
+            confirm_discard_changes(window);
+            glib::Propagation::Stop
+        }
+    ));
 
-            // Update due date filter
-            if date_text.is_empty() {
-                app_state_mut.current_due_date_filter = None; // No due date filter
-            } else if date_text.to_lowercase() == "none" {
-                app_state_mut.current_due_date_filter = Some(None); // Filter for tasks with no due date
-            }
-            else {
-                match NaiveDate::parse_from_str(&date_text, "%Y-%m-%d") {
-                    Ok(date) => app_state_mut.current_due_date_filter = Some(Some(date)),
-                    Err(_) => {
-                        println!("Invalid date format for filter: {}", date_text);
-                        app_state_mut.current_due_date_filter = None; // Clear filter on invalid input
-                    }
+    // Board switcher handlers
+    view.board_switcher.combo.connect_changed(clone!(
+        #[weak]
+        view,
+        move |combo| {
+            let Some(name) = combo.active_id() else {
+                return;
+            };
+            let is_active = view
+                .boards
+                .as_ref()
+                .is_some_and(|boards| boards.borrow().active_board().name == name);
+            if !is_active {
+                view.switch_board(&name);
+            }
+        }
+    ));
+    view.board_switcher.new_button.connect_clicked(clone!(
+        #[weak]
+        view,
+        move |_| {
+            view.board_switcher.close_menu();
+            show_board_name_dialog(&view, "New Board", "Create", "", |boards, name| {
+                boards.create(name).map(|board| board.name.clone())
+            });
+        }
+    ));
+    view.board_switcher.rename_button.connect_clicked(clone!(
+        #[weak]
+        view,
+        move |_| {
+            view.board_switcher.close_menu();
+            let Some(boards) = &view.boards else {
+                return;
+            };
+            let current = boards.borrow().active_board().name.clone();
+            show_board_name_dialog(
+                &view,
+                "Rename Board",
+                "Rename",
+                &current.clone(),
+                move |boards, name| {
+                    boards.rename(&current, name)?;
+                    Ok(name.trim().to_string())
+                },
+            );
+        }
+    ));
+    view.board_switcher.archive_button.connect_clicked(clone!(
+        #[weak]
+        view,
+        move |_| {
+            view.board_switcher.close_menu();
+            let Some(boards) = &view.boards else {
+                return;
+            };
+            let current = boards.borrow().active_board().name.clone();
+            let result = boards.borrow_mut().archive(&current);
+            match result {
+                Ok(()) => {
+                    let next = boards.borrow().active_board().name.clone();
+                    view.switch_board(&next);
                 }
+                Err(e) => view.report_board_error(e),
             }
+        }
+    ));
+    view.board_switcher.unarchive_button.connect_clicked(clone!(
+        #[weak]
+        view,
+        move |_| {
+            view.board_switcher.close_menu();
+            show_unarchive_dialog(&view);
+        }
+    ));
 
-            // Trigger UI refresh
-            drop(app_state_mut);
-            refresh_ui(Rc::clone(&app_state));
-        }),
-    );
-
-    // Clear Filters button handler
-    clear_filters_button.connect_clicked(
-        glib::clone!(@weak category_filter_combo, @weak due_date_filter_entry, @strong app_state, @strong refresh_ui => move |_| {
-            // Clear entry fields
-            due_date_filter_entry.set_text("");
+    // Columns button handler
+    columns_button.connect_clicked(clone!(
+        #[strong]
+        view,
+        move |_| show_columns_dialog(&view)
+    ));
 
-            // Reset category combo box
-            category_filter_combo.set_active_id(Some("All Categories"));
+    // Restore button handler
+    restore_button.connect_clicked(clone!(
+        #[strong]
+        view,
+        move |_| show_restore_dialog(&view)
+    ));
 
-            // Clear filters in app_state
-            let mut app_state_mut = app_state.borrow_mut();
-            app_state_mut.current_category_filter = None;
-            app_state_mut.current_due_date_filter = None;
-            drop(app_state_mut);
+    // Add Task button handler
+    add_button.connect_clicked(clone!(
+        #[weak]
+        entry,
+        #[weak]
+        entry_error_label,
+        #[strong]
+        due_picker,
+        #[strong]
+        quick_add,
+        #[strong]
+        view,
+        move |_| {
+            let mut description = entry.text().to_string();
+            if description.trim().is_empty() {
+                return;
+            }
+            if let Err(error) = check_task_description(&description) {
+                show_field_error(&entry, &entry_error_label, &error.to_string());
+                entry.grab_focus();
+                select_syntax_error(&entry, &description, &error);
+                return;
+            }
+            if let Some(due_time) = due_picker.value() {
+                // Tag the description, ahead of any checklist items
+                let tag = format!(" #{}", due_time.format("%Y-%m-%d_%H:%M"));
+                let end = description.find('|').unwrap_or(description.len());
+                description.insert_str(end, &tag);
+            }
+
+            let result = view.app_state.borrow_mut().add_task(description);
+            view.report(result);
+            entry.set_text(""); // Clear the input field
+            due_picker.set_value(None);
+            quick_add.set_picked_due(None);
+            view.refresh();
+        }
+    ));
+    due_picker.connect_changed(clone!(
+        #[weak]
+        quick_add,
+        move |due| quick_add.set_picked_due(due)
+    ));
+    entry.connect_activate(clone!(
+        #[weak]
+        add_button,
+        move |_| add_button.emit_clicked()
+    ));
+    entry.connect_changed(clone!(
+        #[weak]
+        entry_error_label,
+        move |entry| hide_field_error(entry, &entry_error_label)
+    ));
+
+    view.tag_filter.connect_toggled(clone!(
+        #[weak]
+        view,
+        move |tag, selected| view.toggle_tag_filter(tag, selected)
+    ));
+    view.tag_filter.match_any.connect_toggled(clone!(
+        #[weak]
+        view,
+        move |check| {
+            let tag_match = if check.is_active() {
+                TagMatch::Any
+            } else {
+                TagMatch::All
+            };
+            let changed = {
+                let mut app_state = view.app_state.borrow_mut();
+                let changed = app_state.filter.tag_match != tag_match;
+                app_state.filter.tag_match = tag_match;
+                changed
+            };
+            if changed {
+                view.refresh();
+            }
+        }
+    ));
+
+    // The sort order is saved with the board
+    view.sort_combo.connect_changed(clone!(
+        #[weak]
+        view,
+        move |combo| {
+            let Some(sort) = combo.active_id().and_then(|id| id.parse::<SortMode>().ok()) else {
+                return;
+            };
+            if view.app_state.borrow().sort == sort {
+                return;
+            }
+            let result = view.app_state.borrow_mut().set_sort(sort);
+            view.report(result);
+            view.refresh();
+        }
+    ));
+
+    // Search as you type; Escape clears the search
+    search_entry.connect_search_changed(clone!(
+        #[weak]
+        view,
+        move |entry| {
+            view.app_state.borrow_mut().filter.search = SearchQuery::parse(&entry.text());
+            view.refresh();
+        }
+    ));
+    search_entry.connect_stop_search(|entry| entry.set_text(""));
+
+    // Apply Filter button handler
+    apply_filter_button.connect_clicked(clone!(
+        #[weak]
+        due_date_filter_entry,
+        #[weak]
+        filter_error_label,
+        #[strong]
+        due_filter_picker,
+        #[strong]
+        view,
+        move |_| {
+            let date_text = due_date_filter_entry.text().to_string();
+
+            let mut app_state_mut = view.app_state.borrow_mut();
+
+            // Update due date filter
+            if date_text.is_empty() {
+                app_state_mut.filter.due_date = None; // No due date filter
+                due_filter_picker.set_value(None);
+            } else {
+                match parse_due_filter(&date_text) {
+                    Ok(filter) => {
+                        app_state_mut.filter.due_date = Some(filter);
+                        due_filter_picker.set_value(match filter {
+                            DueFilter::On(date) => Some(date.and_time(NaiveTime::MIN)),
+                            _ => None,
+                        });
+                    }
+                    // Keep the previous date filter until the text is fixed
+                    Err(e) => show_field_error(&due_date_filter_entry, &filter_error_label, &e),
+                }
+            }
 
             // Trigger UI refresh
-            refresh_ui(Rc::clone(&app_state));
-        }),
+            drop(app_state_mut);
+            view.refresh();
+        }
+    ));
+
+    // Smart filter buttons fill in the filter entry, or clear it when their
+    // filter is already on
+    for (filter, button) in &view.smart_filters {
+        let filter = *filter;
+        button.connect_clicked(clone!(
+            #[weak]
+            due_date_filter_entry,
+            #[weak]
+            apply_filter_button,
+            #[weak]
+            view,
+            move |_| {
+                let active = view.app_state.borrow().filter.due_date == Some(filter);
+                let text = if active {
+                    String::new()
+                } else {
+                    filter.to_string()
+                };
+                due_date_filter_entry.set_text(&text);
+                apply_filter_button.emit_clicked();
+            }
+        ));
+    }
+
+    // Fill in the filter entry from the calendar and apply it
+    due_filter_picker.connect_changed(clone!(
+        #[weak]
+        due_date_filter_entry,
+        #[weak]
+        apply_filter_button,
+        move |date| {
+            let text = date.map(|date| date.format("%Y-%m-%d").to_string());
+            due_date_filter_entry.set_text(text.as_deref().unwrap_or(""));
+            apply_filter_button.emit_clicked();
+        }
+    ));
+    due_date_filter_entry.connect_changed(clone!(
+        #[weak]
+        filter_error_label,
+        move |entry| hide_field_error(entry, &filter_error_label)
+    ));
+    due_date_filter_entry.connect_activate(clone!(
+        #[weak]
+        apply_filter_button,
+        move |_| apply_filter_button.emit_clicked()
+    ));
+
+    // Clear Filters button handler
+    clear_filters_button.connect_clicked(clone!(
+        #[weak]
+        search_entry,
+        #[weak]
+        due_date_filter_entry,
+        #[strong]
+        due_filter_picker,
+        #[strong]
+        view,
+        move |_| {
+            // Clear entry fields
+            search_entry.set_text("");
+            due_date_filter_entry.set_text("");
+            due_filter_picker.set_value(None);
+
+            // Clear filters in app_state; refreshing unticks the tags
+            view.app_state.borrow_mut().filter = Default::default();
+
+            // Trigger UI refresh
+            view.refresh();
+        }
+    ));
+
+    // Saved filters fill in the filter fields as if they had been typed
+    let saved_filters = Rc::new(saved_filters);
+    let apply_saved_filter = gio::SimpleAction::new("apply-filter", Some(glib::VariantTy::STRING));
+    apply_saved_filter.connect_activate(clone!(
+        #[weak]
+        search_entry,
+        #[weak]
+        due_date_filter_entry,
+        #[strong]
+        due_filter_picker,
+        #[weak]
+        view,
+        #[strong]
+        saved_filters,
+        move |_, parameter| {
+            let Some(name) = parameter.and_then(|p| p.get::<String>()) else {
+                return;
+            };
+            let Some(saved) = saved_filters.get(&name) else {
+                return;
+            };
+            let filter = match saved.to_filter() {
+                Ok(filter) => filter,
+                Err(e) => {
+                    view.error_banner
+                        .show(&format!("Could not apply the filter {}: {}.", name, e));
+                    return;
+                }
+            };
+            search_entry.set_text(&saved.search);
+            let due_text = filter.due_date.map(|due| due.to_string());
+            due_date_filter_entry.set_text(due_text.as_deref().unwrap_or(""));
+            due_filter_picker.set_value(match filter.due_date {
+                Some(DueFilter::On(date)) => Some(date.and_time(NaiveTime::MIN)),
+                _ => None,
+            });
+            view.app_state.borrow_mut().filter = filter;
+            view.refresh();
+        }
+    ));
+    window.add_action(&apply_saved_filter);
+
+    // Ctrl+K finds any action, task, filter or board by name
+    let palette = CommandPalette::new(&window);
+    let palette_action = gio::SimpleAction::new("command-palette", None);
+    palette_action.connect_activate(clone!(
+        #[weak]
+        view,
+        #[strong]
+        saved_filters,
+        move |_, _| palette.open(palette_commands(&view, &saved_filters))
+    ));
+    window.add_action(&palette_action);
+}
+
+/// What the command palette offers: the enabled actions that take no
+/// parameter, then for each task jumping to it, moving it and changing its
+/// priority, then the filters and boards.
+fn palette_commands(
+    view: &Rc<BoardView>,
+    saved_filters: &BTreeMap<String, SavedFilter>,
+) -> Vec<Command> {
+    let mut commands = Vec::new();
+    let Some(app) = view.window.application() else {
+        return commands;
+    };
+
+    let groups = [
+        ("app", app.clone().upcast::<gio::ActionGroup>()),
+        ("win", view.window.clone().upcast::<gio::ActionGroup>()),
+    ];
+    for (prefix, group) in groups {
+        let mut names = group.list_actions();
+        names.sort();
+        for name in names {
+            let detailed = format!("{}.{}", prefix, name);
+            if detailed == "win.command-palette"
+                || group.action_parameter_type(&name).is_some()
+                || !group.is_action_enabled(&name)
+            {
+                continue;
+            }
+            let title = shortcuts::title(&detailed).map_or_else(
+                || {
+                    let mut title = name.replace('-', " ");
+                    title[..1].make_ascii_uppercase();
+                    title
+                },
+                str::to_string,
+            );
+            let hint = shortcuts::accel_label(&app, &detailed);
+            commands.push(Command::new(
+                title,
+                hint,
+                clone!(
+                    #[weak]
+                    group,
+                    move || group.activate_action(&name, None)
+                ),
+            ));
+        }
+    }
+
+    let app_state = view.app_state.borrow();
+    let workflow = &app_state.workflow;
+    for task in &app_state.tasks {
+        let task_id = task.id;
+        let column_name = workflow
+            .column(&task.status)
+            .map_or_else(|| task.status.clone(), |column| column.name.clone());
+        commands.push(Command::new(
+            format!("Go to {}", task.description),
+            column_name,
+            clone!(
+                #[weak]
+                view,
+                #[weak]
+                app,
+                move || {
+                    if view.row_for(task_id).is_none() {
+                        app.activate_action("clear-filters", None);
+                    }
+                    view.focus_task(task_id);
+                }
+            ),
+        ));
+        for column in workflow.columns.iter().filter(|c| c.id != task.status) {
+            let column_id = column.id.clone();
+            commands.push(Command::new(
+                format!("Move {} to {}", task.description, column.name),
+                "",
+                clone!(
+                    #[weak]
+                    view,
+                    move || view.move_task(task_id, &column_id, None, false)
+                ),
+            ));
+        }
+        for priority in [Priority::High, Priority::Medium, Priority::Low] {
+            if priority == task.priority {
+                continue;
+            }
+            commands.push(Command::new(
+                format!("Set priority of {} to {:?}", task.description, priority),
+                "",
+                clone!(
+                    #[weak]
+                    view,
+                    move || view.set_task_priority(task_id, priority.clone())
+                ),
+            ));
+        }
+    }
+
+    for (filter, button) in &view.smart_filters {
+        let filter = *filter;
+        if app_state.filter.due_date == Some(filter) {
+            continue;
+        }
+        commands.push(Command::new(
+            format!("Filter: {}", button.label().unwrap_or_default()),
+            "Due date",
+            clone!(
+                #[weak]
+                button,
+                move || button.emit_clicked()
+            ),
+        ));
+    }
+    for tag in app_state.all_tags() {
+        if app_state.filter.tags.contains(&tag) {
+            continue;
+        }
+        commands.push(Command::new(
+            format!("Filter: #{}", tag),
+            "Tag",
+            clone!(
+                #[weak]
+                view,
+                move || view.toggle_tag_filter(&tag, true)
+            ),
+        ));
+    }
+    let window = &view.window;
+    for sort in SortMode::ALL {
+        if sort == app_state.sort {
+            continue;
+        }
+        commands.push(Command::new(
+            format!("Sort by {}", sort.label()),
+            "Sort order",
+            clone!(
+                #[weak]
+                view,
+                move || {
+                    view.sort_combo.set_active_id(Some(&sort.to_string()));
+                }
+            ),
+        ));
+    }
+    for name in saved_filters.keys() {
+        let name = name.clone();
+        commands.push(Command::new(
+            format!("Filter: {}", name),
+            "Saved filter",
+            clone!(
+                #[weak]
+                window,
+                move || ActionGroupExt::activate_action(
+                    &window,
+                    "apply-filter",
+                    Some(&name.to_variant())
+                )
+            ),
+        ));
+    }
+
+    if let Some(boards) = &view.boards {
+        let boards = boards.borrow();
+        let active = &boards.active_board().name;
+        for board in boards.visible_boards().filter(|b| &b.name != active) {
+            let name = board.name.clone();
+            commands.push(Command::new(
+                format!("Switch to board {}", name),
+                "Board",
+                clone!(
+                    #[weak]
+                    view,
+                    move || {
+                        view.board_switcher.combo.set_active_id(Some(&name));
+                    }
+                ),
+            ));
+        }
+    }
+    commands
+}
+
+/// The task id a notification button was activated with.
+fn notification_task(parameter: Option<&glib::Variant>) -> Option<uuid::Uuid> {
+    let id = parameter?.get::<String>()?;
+    uuid::Uuid::parse_str(&id).ok()
+}
+
+/// A hidden label for reporting invalid input below a row of fields.
+fn field_error_label() -> Label {
+    let label = Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .visible(false)
+        .build();
+    label.add_css_class("field-error-label");
+    label
+}
+
+/// Marks `entry` as invalid and explains why in `label`.
+fn show_field_error(entry: &Entry, label: &Label, message: &str) {
+    entry.add_css_class("entry-error");
+    label.set_text(message);
+    label.set_visible(true);
+}
+
+fn hide_field_error(entry: &Entry, label: &Label) {
+    entry.remove_css_class("entry-error");
+    label.set_visible(false);
+}
+
+/// Selects the tag a quick-add mistake is about, so it can be retyped.
+fn select_syntax_error(entry: &Entry, text: &str, error: &SyntaxError) {
+    // Entries count characters, the error counts bytes
+    let start = text[..error.span.start].chars().count() as i32;
+    let end = text[..error.span.end].chars().count() as i32;
+    entry.select_region(start, end);
+}
+
+/// Lets the user add, rename, reorder and remove the board's columns and
+/// choose which ones count as completed. Nothing changes until Save.
+fn show_columns_dialog(view: &Rc<BoardView>) {
+    let workflow = Rc::new(RefCell::new(view.app_state.borrow().workflow.clone()));
+
+    let dialog = Dialog::with_buttons(
+        Some("Board Columns"),
+        Some(&view.window),
+        gtk::DialogFlags::MODAL,
+        &[("Save", ResponseType::Ok), ("Cancel", ResponseType::Cancel)],
+    );
+    dialog.add_css_class("confirm-dialog");
+    dialog.set_default_size(480, 400);
+
+    let list_box = ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+    list_box.add_css_class("task-list-box");
+    fill_column_rows(&list_box, &workflow);
+
+    let new_column_entry = Entry::builder()
+        .placeholder_text("New column name")
+        .hexpand(true)
+        .build();
+    new_column_entry.add_css_class("task-entry");
+    let add_button = Button::builder().label("Add Column").build();
+    add_button.add_css_class("action-button-small");
+    let add_hbox = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(10)
+        .build();
+    add_hbox.append(&new_column_entry);
+    add_hbox.append(&add_button);
+
+    let error_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .visible(false)
+        .build();
+    error_label.add_css_class("error-banner-label");
+
+    let content = dialog.content_area();
+    content.set_spacing(10);
+    content.append(&Label::new(Some(
+        "Tasks in a removed column move to the first column.",
+    )));
+    content.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .child(&list_box)
+            .vexpand(true)
+            .build(),
     );
+    content.append(&add_hbox);
+    content.append(&error_label);
+
+    add_button.connect_clicked(clone!(
+        #[strong]
+        workflow,
+        #[weak]
+        list_box,
+        #[weak]
+        new_column_entry,
+        #[weak]
+        error_label,
+        move |_| {
+            let result = workflow
+                .borrow_mut()
+                .add_column(&new_column_entry.text())
+                .map(|_| ());
+            match result {
+                Ok(()) => {
+                    new_column_entry.set_text("");
+                    error_label.set_visible(false);
+                    fill_column_rows(&list_box, &workflow);
+                }
+                Err(e) => {
+                    error_label.set_text(&e.to_string());
+                    error_label.set_visible(true);
+                }
+            }
+        }
+    ));
+    new_column_entry.connect_activate(clone!(
+        #[weak]
+        add_button,
+        move |_| add_button.emit_clicked()
+    ));
+
+    dialog.connect_response(clone!(
+        #[weak]
+        view,
+        #[weak]
+        error_label,
+        move |dialog, response| {
+            if response != ResponseType::Ok {
+                dialog.close();
+                return;
+            }
+            let result = view
+                .app_state
+                .borrow_mut()
+                .set_workflow(workflow.borrow().clone());
+            match result {
+                Ok(()) => view.error_banner.hide(),
+                Err(WorkflowError::Storage(e)) => {
+                    view.report::<()>(Err(e));
+                }
+                Err(e) => {
+                    error_label.set_text(&e.to_string());
+                    error_label.set_visible(true);
+                    return;
+                }
+            }
+            dialog.close();
+            view.refresh();
+        }
+    ));
+    dialog.present();
+}
+
+/// Shows one editable row per column in the columns dialog. Rebuilt after
+/// every change to the order or number of columns.
+fn fill_column_rows(list_box: &ListBox, workflow: &Rc<RefCell<Workflow>>) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+
+    let columns = workflow.borrow().columns.clone();
+    for (index, column) in columns.iter().enumerate() {
+        let hbox = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(5)
+            .margin_top(5)
+            .margin_bottom(5)
+            .build();
+
+        let name_entry = Entry::builder().text(&column.name).hexpand(true).build();
+        name_entry.add_css_class("task-entry");
+        name_entry.connect_changed(clone!(
+            #[strong]
+            workflow,
+            move |entry| {
+                workflow.borrow_mut().columns[index].name = entry.text().trim().to_string();
+            }
+        ));
+
+        let completed_check = gtk::CheckButton::builder()
+            .label("Completed")
+            .active(column.completed)
+            .tooltip_text("Show tasks in this column as done")
+            .build();
+        completed_check.connect_toggled(clone!(
+            #[strong]
+            workflow,
+            move |check| {
+                workflow.borrow_mut().columns[index].completed = check.is_active();
+            }
+        ));
+
+        let limit_spin = gtk::SpinButton::with_range(0.0, 999.0, 1.0);
+        limit_spin.set_value(column.wip_limit.unwrap_or(0) as f64);
+        limit_spin.set_tooltip_text(Some("Work-in-progress limit, 0 for none"));
+        let strict_check = gtk::CheckButton::builder()
+            .label("Strict")
+            .active(column.strict_limit)
+            .tooltip_text("Ask before moving a task into the column once the limit is reached")
+            .build();
+        limit_spin.connect_value_changed(clone!(
+            #[strong]
+            workflow,
+            move |spin| {
+                let limit = spin.value_as_int() as usize;
+                workflow.borrow_mut().columns[index].wip_limit = (limit > 0).then_some(limit);
+            }
+        ));
+        strict_check.connect_toggled(clone!(
+            #[strong]
+            workflow,
+            move |check| {
+                workflow.borrow_mut().columns[index].strict_limit = check.is_active();
+            }
+        ));
+
+        let up_button = Button::builder()
+            .label("↑")
+            .tooltip_text("Move left")
+            .sensitive(index > 0)
+            .build();
+        let down_button = Button::builder()
+            .label("↓")
+            .tooltip_text("Move right")
+            .sensitive(index + 1 < columns.len())
+            .build();
+        let remove_button = Button::builder()
+            .label("Remove")
+            .sensitive(columns.len() > 1)
+            .build();
+        up_button.add_css_class("action-button-small");
+        down_button.add_css_class("action-button-small");
+        remove_button.add_css_class("delete-button-small");
+
+        for (button, new_index) in [
+            (&up_button, index.saturating_sub(1)),
+            (&down_button, index + 1),
+        ] {
+            let id = column.id.clone();
+            button.connect_clicked(clone!(
+                #[strong]
+                workflow,
+                #[weak]
+                list_box,
+                move |_| {
+                    if workflow.borrow_mut().move_column(&id, new_index).is_ok() {
+                        fill_column_rows(&list_box, &workflow);
+                    }
+                }
+            ));
+        }
+        let id = column.id.clone();
+        remove_button.connect_clicked(clone!(
+            #[strong]
+            workflow,
+            #[weak]
+            list_box,
+            move |_| {
+                if workflow.borrow_mut().remove_column(&id).is_ok() {
+                    fill_column_rows(&list_box, &workflow);
+                }
+            }
+        ));
+
+        hbox.append(&name_entry);
+        hbox.append(&completed_check);
+        hbox.append(&limit_spin);
+        hbox.append(&strict_check);
+        hbox.append(&up_button);
+        hbox.append(&down_button);
+        hbox.append(&remove_button);
+        list_box.append(&ListBoxRow::builder().child(&hbox).build());
+    }
+}
+
+/// Edits every field of a task in one place: description, tags,
+/// priority, due date, repeat rule, reminders and notes. Changes are applied on Save.
+fn show_task_details(view: &Rc<BoardView>, task_id: uuid::Uuid) {
+    let Some(task) = view.app_state.borrow().find_task(task_id).cloned() else {
+        return;
+    };
+
+    let dialog = Dialog::with_buttons(
+        Some("Task Details"),
+        Some(&view.window),
+        gtk::DialogFlags::MODAL,
+        &[("Save", ResponseType::Ok), ("Cancel", ResponseType::Cancel)],
+    );
+    dialog.add_css_class("edit-dialog");
+    dialog.set_default_size(520, -1);
+    dialog.set_default_response(ResponseType::Ok);
+
+    let description_entry = Entry::builder()
+        .text(&task.description)
+        .activates_default(true)
+        .hexpand(true)
+        .build();
+    description_entry.add_css_class("task-entry");
+
+    let tags_entry = Entry::builder()
+        .text(
+            task.tags
+                .iter()
+                .map(|tag| format!("#{}", tag))
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+        .placeholder_text("None")
+        .activates_default(true)
+        .build();
+    tags_entry.add_css_class("task-entry");
+
+    let priority_combo = ComboBoxText::new();
+    for priority in [Priority::High, Priority::Medium, Priority::Low] {
+        let name = format!("{:?}", priority);
+        priority_combo.append(Some(&name), &name);
+    }
+    priority_combo.set_active_id(Some(&format!("{:?}", task.priority)));
+    priority_combo.add_css_class("edit-priority-combo");
+
+    let due_picker = DatePicker::new();
+    due_picker.set_value(task.due_time);
+    due_picker.button.set_halign(gtk::Align::Start);
+
+    let repeats_entry = Entry::builder()
+        .text(task.recurrence.as_ref().map_or_else(String::new, |rule| {
+            rule.to_tags()
+                .iter()
+                .map(|tag| format!("#{}", tag))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }))
+        .placeholder_text("Never, or e.g. #every-mon, #monthly, #every-2-weeks")
+        .activates_default(true)
+        .build();
+    repeats_entry.add_css_class("task-entry");
+
+    let default_reminders = format_reminder_minutes(&view.app_state.borrow().reminder_minutes);
+    let reminders_entry = Entry::builder()
+        .text(
+            task.reminders
+                .as_deref()
+                .map_or_else(String::new, format_reminder_minutes),
+        )
+        .placeholder_text(format!("Default: {}", default_reminders))
+        .tooltip_text("Minutes before the due time, such as 0, 15; or off")
+        .activates_default(true)
+        .build();
+    reminders_entry.add_css_class("task-entry");
+
+    let notes_buffer = gtk::TextBuffer::new(None);
+    notes_buffer.set_text(&task.notes);
+    let notes_view = gtk::TextView::builder()
+        .buffer(&notes_buffer)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .monospace(true)
+        .build();
+    notes_view.add_css_class("notes-view");
+    let notes_scroll = ScrolledWindow::builder()
+        .child(&notes_view)
+        .min_content_height(160)
+        .vexpand(true)
+        .build();
+
+    let grid = gtk::Grid::builder()
+        .row_spacing(10)
+        .column_spacing(10)
+        .build();
+    let fields: [(&str, &gtk::Widget); 7] = [
+        ("Description", description_entry.upcast_ref()),
+        ("Tags", tags_entry.upcast_ref()),
+        ("Priority", priority_combo.upcast_ref()),
+        ("Due", due_picker.button.upcast_ref()),
+        ("Repeats", repeats_entry.upcast_ref()),
+        ("Remind", reminders_entry.upcast_ref()),
+        ("Notes", notes_scroll.upcast_ref()),
+    ];
+    for (row, (name, widget)) in (0..).zip(fields) {
+        let label = Label::builder()
+            .label(name)
+            .halign(gtk::Align::End)
+            .valign(gtk::Align::Start)
+            .margin_top(6)
+            .build();
+        grid.attach(&label, 0, row, 1, 1);
+        grid.attach(widget, 1, row, 1, 1);
+    }
+
+    let format_time = |time: Option<chrono::DateTime<chrono::Utc>>| {
+        time.map_or_else(
+            || "unknown".to_string(),
+            |time| {
+                time.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            },
+        )
+    };
+    let timestamps_label = Label::builder()
+        .label(format!(
+            "Created {} · Updated {}",
+            format_time(task.created_at),
+            format_time(task.updated_at)
+        ))
+        .halign(gtk::Align::Start)
+        .build();
+    timestamps_label.add_css_class("timestamps-label");
+
+    let error_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .visible(false)
+        .build();
+    error_label.add_css_class("error-banner-label");
+
+    let content = dialog.content_area();
+    content.set_spacing(10);
+    content.append(&grid);
+    content.append(&timestamps_label);
+    content.append(&error_label);
+
+    dialog.connect_response(clone!(
+        #[weak]
+        view,
+        #[weak]
+        description_entry,
+        #[weak]
+        tags_entry,
+        #[weak]
+        priority_combo,
+        #[weak]
+        repeats_entry,
+        #[weak]
+        reminders_entry,
+        #[weak]
+        notes_buffer,
+        #[weak]
+        error_label,
+        move |dialog, response| {
+            if response != ResponseType::Ok {
+                dialog.close();
+                return;
+            }
+            let description = description_entry.text().trim().to_string();
+            if description.is_empty() {
+                error_label.set_text("A task needs a description.");
+                error_label.set_visible(true);
+                return;
+            }
+            // Tags are lowercase words without the `#`, as the quick-add parser makes them
+            let mut tags: Vec<String> = Vec::new();
+            for word in tags_entry.text().split_whitespace() {
+                let tag = word.trim_start_matches('#').to_lowercase();
+                if !tag.is_empty() && !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+            // The same tags as the quick-add line, with or without their `#`
+            let repeat_line = repeats_entry
+                .text()
+                .split_whitespace()
+                .map(|word| format!("#{}", word.trim_start_matches('#')))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let parsed_repeat = parse_task_description(&repeat_line);
+            if !repeat_line.is_empty()
+                && (parsed_repeat.recurrence.is_none() || !parsed_repeat.tags.is_empty())
+            {
+                error_label.set_text(&format!(
+                    "\"{}\" is not a repeat rule, such as #every-mon or #monthly.",
+                    repeats_entry.text()
+                ));
+                error_label.set_visible(true);
+                return;
+            }
+            let reminders = match parse_reminder_minutes(&reminders_entry.text()) {
+                Ok(reminders) => reminders,
+                Err(e) => {
+                    error_label.set_text(&e);
+                    error_label.set_visible(true);
+                    return;
+                }
+            };
+            let priority = match priority_combo.active_id().as_deref() {
+                Some("High") => Priority::High,
+                Some("Low") => Priority::Low,
+                _ => Priority::Medium,
+            };
+            let (start, end) = notes_buffer.bounds();
+
+            let edited = Task {
+                description,
+                tags,
+                priority,
+                due_time: due_picker.value(),
+                recurrence: parsed_repeat.recurrence,
+                reminders,
+                notes: notes_buffer.text(&start, &end, false).to_string(),
+                ..task.clone()
+            };
+            let result = view.app_state.borrow_mut().update_task(edited);
+            view.report(result);
+            view.refresh();
+            dialog.close();
+        }
+    ));
+    dialog.present();
+}
+
+/// `0, 15` for reminders at the due time and 15 minutes before, or `off`.
+fn format_reminder_minutes(minutes: &[u32]) -> String {
+    if minutes.is_empty() {
+        return "off".to_string();
+    }
+    minutes
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Reads what [`format_reminder_minutes`] writes; blank means the
+/// configured reminders.
+fn parse_reminder_minutes(text: &str) -> Result<Option<Vec<u32>>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    if text.eq_ignore_ascii_case("off") {
+        return Ok(Some(Vec::new()));
+    }
+    text.split([',', ' '])
+        .filter(|minutes| !minutes.is_empty())
+        .map(|minutes| {
+            minutes.parse().map_err(|_| {
+                format!(
+                    "\"{}\" is not a number of minutes before the due time.",
+                    minutes
+                )
+            })
+        })
+        .collect::<Result<Vec<u32>, _>>()
+        .map(Some)
+}
+
+/// Lets the user pick one of the backups kept next to the data file and
+/// replaces the current tasks with it.
+fn show_restore_dialog(view: &Rc<BoardView>) {
+    let dialog = Dialog::with_buttons(
+        Some("Restore from Backup"),
+        Some(&view.window),
+        gtk::DialogFlags::MODAL,
+        &[
+            ("Restore", ResponseType::Ok),
+            ("Cancel", ResponseType::Cancel),
+        ],
+    );
+    dialog.add_css_class("confirm-dialog");
+    dialog.set_default_size(420, 360);
+    dialog.set_default_response(ResponseType::Cancel);
+
+    let backups = view.app_state.borrow().list_backups().unwrap_or_else(|e| {
+        eprintln!("Error listing backups: {}", e);
+        Vec::new()
+    });
+
+    let content = dialog.content_area();
+    content.set_spacing(10);
+    if backups.is_empty() {
+        content.append(&Label::new(Some("No backups have been made yet.")));
+        dialog.set_response_sensitive(ResponseType::Ok, false);
+    } else {
+        content.append(&Label::new(Some(
+            "The current tasks are backed up before restoring.",
+        )));
+
+        let list_box = ListBox::builder()
+            .selection_mode(gtk::SelectionMode::Single)
+            .build();
+        list_box.add_css_class("task-list-box");
+        for backup in &backups {
+            let summary = match todo_core::read_tasks(&backup.path) {
+                Ok(data) => format!("{} tasks", data.tasks.len()),
+                Err(_) => "unreadable".to_string(),
+            };
+            let label = Label::builder()
+                .label(format!(
+                    "{} — {}",
+                    backup.created.format("%Y-%m-%d %H:%M:%S"),
+                    summary
+                ))
+                .halign(gtk::Align::Start)
+                .build();
+            list_box.append(&ListBoxRow::builder().child(&label).build());
+        }
+        list_box.select_row(list_box.row_at_index(0).as_ref());
+
+        let scrolled_window = ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .child(&list_box)
+            .vexpand(true)
+            .build();
+        content.append(&scrolled_window);
+
+        dialog.connect_response(clone!(
+            #[weak]
+            view,
+            #[weak]
+            list_box,
+            move |_, response| {
+                if response != ResponseType::Ok {
+                    return;
+                }
+                let Some(backup) = list_box
+                    .selected_row()
+                    .and_then(|row| backups.get(row.index() as usize))
+                else {
+                    return;
+                };
+                let result = view.app_state.borrow_mut().restore_backup(&backup.path);
+                view.report(result);
+                view.refresh();
+            }
+        ));
+    }
+
+    dialog.connect_response(|dialog, _| dialog.close());
+    dialog.present();
+}
+
+/// Asks for a board name and hands it to `apply`, which returns the board to
+/// switch to. Errors are shown in the dialog so the name can be corrected.
+fn show_board_name_dialog(
+    view: &Rc<BoardView>,
+    title: &str,
+    accept_label: &str,
+    initial_name: &str,
+    apply: impl Fn(&mut BoardRegistry, &str) -> Result<String, BoardError> + 'static,
+) {
+    let dialog = Dialog::with_buttons(
+        Some(title),
+        Some(&view.window),
+        gtk::DialogFlags::MODAL,
+        &[
+            (accept_label, ResponseType::Ok),
+            ("Cancel", ResponseType::Cancel),
+        ],
+    );
+    dialog.add_css_class("confirm-dialog");
+    dialog.set_default_response(ResponseType::Ok);
+
+    let name_entry = Entry::builder()
+        .text(initial_name)
+        .placeholder_text("Board name")
+        .activates_default(true)
+        .build();
+    name_entry.add_css_class("task-entry");
+    let error_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .visible(false)
+        .build();
+    error_label.add_css_class("error-banner-label");
+
+    let content = dialog.content_area();
+    content.set_spacing(10);
+    content.append(&name_entry);
+    content.append(&error_label);
+
+    dialog.connect_response(clone!(
+        #[weak]
+        view,
+        #[weak]
+        name_entry,
+        #[weak]
+        error_label,
+        move |dialog, response| {
+            if response != ResponseType::Ok {
+                dialog.close();
+                return;
+            }
+            let Some(boards) = &view.boards else {
+                dialog.close();
+                return;
+            };
+            let result = apply(&mut boards.borrow_mut(), &name_entry.text());
+            match result {
+                Ok(name) => {
+                    dialog.close();
+                    view.board_switcher.set_boards(&boards.borrow());
+                    view.switch_board(&name);
+                }
+                Err(e) => {
+                    error_label.set_text(&e.to_string());
+                    error_label.set_visible(true);
+                }
+            }
+        }
+    ));
+    dialog.present();
+}
+
+/// Lets the user bring back one of the archived boards and switches to it.
+fn show_unarchive_dialog(view: &Rc<BoardView>) {
+    let Some(boards) = &view.boards else {
+        return;
+    };
+    let archived: Vec<String> = boards
+        .borrow()
+        .boards
+        .iter()
+        .filter(|b| b.archived)
+        .map(|b| b.name.clone())
+        .collect();
+
+    let dialog = Dialog::with_buttons(
+        Some("Unarchive Board"),
+        Some(&view.window),
+        gtk::DialogFlags::MODAL,
+        &[
+            ("Unarchive", ResponseType::Ok),
+            ("Cancel", ResponseType::Cancel),
+        ],
+    );
+    dialog.add_css_class("confirm-dialog");
+    dialog.set_default_size(320, 280);
+
+    let list_box = ListBox::builder()
+        .selection_mode(gtk::SelectionMode::Single)
+        .build();
+    list_box.add_css_class("task-list-box");
+    for name in &archived {
+        let label = Label::builder()
+            .label(name)
+            .halign(gtk::Align::Start)
+            .build();
+        list_box.append(&ListBoxRow::builder().child(&label).build());
+    }
+    list_box.select_row(list_box.row_at_index(0).as_ref());
+    dialog.content_area().append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .child(&list_box)
+            .vexpand(true)
+            .build(),
+    );
+
+    dialog.connect_response(clone!(
+        #[weak]
+        view,
+        #[weak]
+        list_box,
+        move |dialog, response| {
+            dialog.close();
+            if response != ResponseType::Ok {
+                return;
+            }
+            let (Some(boards), Some(name)) = (
+                &view.boards,
+                list_box
+                    .selected_row()
+                    .and_then(|row| archived.get(row.index() as usize)),
+            ) else {
+                return;
+            };
+            let result = boards.borrow_mut().unarchive(name);
+            match result {
+                Ok(()) => view.switch_board(name),
+                Err(e) => view.report_board_error(e),
+            }
+        }
+    ));
+    dialog.present();
+}
+
+/// Asks for a new location for the data file and saves there.
+fn show_save_as_dialog(view: &Rc<BoardView>) {
+    let dialog = gtk::FileChooserDialog::new(
+        Some("Save Tasks As"),
+        Some(&view.window),
+        gtk::FileChooserAction::Save,
+        &[
+            ("Cancel", ResponseType::Cancel),
+            ("Save", ResponseType::Accept),
+        ],
+    );
+    dialog.set_modal(true);
+    if let Some(name) = view.app_state.borrow().file_path.file_name() {
+        dialog.set_current_name(&name.to_string_lossy());
+    }
+
+    dialog.connect_response(clone!(
+        #[weak]
+        view,
+        move |dialog, response| {
+            if response == ResponseType::Accept
+                && let Some(path) = dialog.file().and_then(|file| file.path())
+            {
+                let result = view.app_state.borrow_mut().save_as(path);
+                view.report(result);
+            }
+            dialog.close();
+        }
+    ));
+    dialog.present();
+}
+
+/// Shown when a move is refused by a strict WIP limit; the user can still
+/// make the move.
+fn confirm_over_limit(
+    view: &Rc<BoardView>,
+    error: &MoveError,
+    task_id: uuid::Uuid,
+    status: String,
+    before: Option<uuid::Uuid>,
+) {
+    let dialog = Dialog::with_buttons(
+        Some("Work-in-Progress Limit Reached"),
+        Some(&view.window),
+        gtk::DialogFlags::MODAL,
+        &[
+            ("Move Anyway", ResponseType::Ok),
+            ("Cancel", ResponseType::Cancel),
+        ],
+    );
+    dialog.add_css_class("confirm-dialog");
+    dialog
+        .content_area()
+        .append(&Label::new(Some(&format!("{}.", error))));
+    dialog.set_default_response(ResponseType::Cancel);
+
+    dialog.connect_response(clone!(
+        #[weak]
+        view,
+        move |dialog, response| {
+            dialog.close();
+            if response == ResponseType::Ok {
+                view.move_task(task_id, &status, before, true);
+            }
+        }
+    ));
+    dialog.present();
+}
+
+/// Shown when the window is closed while the last save failed.
+fn confirm_discard_changes(window: &ApplicationWindow) {
+    let dialog = Dialog::with_buttons(
+        Some("Unsaved Changes"),
+        Some(window),
+        gtk::DialogFlags::MODAL,
+        &[
+            ("Quit Without Saving", ResponseType::Reject),
+            ("Cancel", ResponseType::Cancel),
+        ],
+    );
+    dialog.add_css_class("confirm-dialog");
+    dialog.content_area().append(&Label::new(Some(
+        "Your latest changes could not be saved and will be lost if you quit.",
+    )));
+    dialog.set_default_response(ResponseType::Cancel);
+
+    dialog.connect_response(clone!(
+        #[weak]
+        window,
+        move |dialog, response| {
+            dialog.close();
+            if response == ResponseType::Reject {
+                window.destroy();
+            }
+        }
+    ));
+    dialog.present();
 }
 
 /// Helper function to create a task column
-fn create_task_column(title: &str, list_box: &ListBox) -> Box {
+fn create_task_column(title_label: &Label, list_box: &ListBox) -> Box {
     let vbox = Box::builder()
         .orientation(Orientation::Vertical)
         .spacing(10)
@@ -584,9 +2582,8 @@ fn create_task_column(title: &str, list_box: &ListBox) -> Box {
         .build();
     vbox.add_css_class("column-container");
 
-    let label = Label::builder().label(title).build();
-    label.add_css_class("column-title");
-    vbox.append(&label);
+    title_label.add_css_class("column-title");
+    vbox.append(title_label);
 
     let scrolled_window = ScrolledWindow::builder()
         .hscrollbar_policy(gtk::PolicyType::Never)
@@ -601,14 +2598,7 @@ fn create_task_column(title: &str, list_box: &ListBox) -> Box {
 }
 
 // Creates a ListBoxRow for a single task.
-fn create_task_row(
-    task: Task,
-    app_state: Rc<RefCell<AppState>>,
-    todo_list_box_rc: Rc<ListBox>,
-    doing_list_box_rc: Rc<ListBox>,
-    done_list_box_rc: Rc<ListBox>,
-    main_window_rc: Rc<ApplicationWindow>,
-) -> ListBoxRow {
+fn create_task_row(task: &Task, workflow: &Workflow, view: &Rc<BoardView>) -> ListBoxRow {
     let row = ListBoxRow::new();
     let hbox = Box::builder()
         .orientation(Orientation::Horizontal)
@@ -622,7 +2612,7 @@ fn create_task_row(
 
     // Display Priority
     let priority_label = Label::builder()
-        .label(&format!("{:?}", task.priority))
+        .label(format!("{:?}", task.priority))
         .halign(gtk::Align::Start)
         .build();
     priority_label.add_css_class(&format!(
@@ -634,11 +2624,6 @@ fn create_task_row(
     // Start with the basic description
     let mut display_text = task.description.clone();
 
-    // Append category if present
-    if let Some(category) = &task.category {
-        display_text.push_str(&format!(" #{}", category));
-    }
-
     // Append due time if present
     if let Some(due_time) = &task.due_time {
         display_text.push_str(&format!(" (Due: {})", due_time.format("%Y-%m-%d %H:%M")));
@@ -652,164 +2637,552 @@ fn create_task_row(
         .has_frame(false) // Initially no frame
         .build();
     task_entry.add_css_class("task-description"); // Keep the class for styling
+    if !task.notes.is_empty() {
+        task_entry.set_tooltip_text(Some(&task.notes));
+    }
+    let highlights = view
+        .app_state
+        .borrow()
+        .filter
+        .search
+        .highlights(&display_text);
+    if !highlights.is_empty() {
+        task_entry.set_attributes(&search_highlight_attributes(&highlights));
+    }
 
-    // Apply 'completed' style if task is done
-    if task.status == TaskStatus::Done {
+    // Apply 'completed' style if the task's column counts as completed,
+    // otherwise highlight it by how soon it is due
+    if workflow.is_completed(&task.status) {
         task_entry.add_css_class("completed-task");
+    } else if let Some(urgency) = task
+        .due_time
+        .and_then(|due_time| Urgency::of(due_time, Local::now().naive_local()))
+    {
+        row.add_css_class(match urgency {
+            Urgency::Overdue => "due-overdue",
+            Urgency::Today => "due-today",
+            Urgency::ThisWeek => "due-this-week",
+        });
     }
 
     // Buttons for actions
-    // Removed the "Edit" button as it's no longer needed for direct editing
+    let next_column = workflow.next_after(&task.status);
+    let move_label = if next_column.completed {
+        format!("Mark {}", next_column.name)
+    } else if next_column.id == workflow.first().id {
+        "Revert".to_string()
+    } else {
+        format!("Move to {}", next_column.name)
+    };
+    let move_button = Button::builder().label(move_label).build();
+    move_button.add_css_class("action-button-small");
 
-    let move_button = Button::builder()
-        .label(match task.status {
-            TaskStatus::Todo => "Start Doing",
-            TaskStatus::Doing => "Mark Done",
-            TaskStatus::Done => "Revert",
-        })
+    let details_button = Button::builder()
+        .label("Details")
+        .tooltip_text("Edit notes, priority, due date and more")
         .build();
-    move_button.add_css_class("action-button-small");
+    details_button.add_css_class("action-button-small");
 
     let delete_button = Button::builder().label("Delete").build();
     delete_button.add_css_class("delete-button-small");
 
-    // Connect Signals for this row
-    let refresh_ui_for_row = glib::clone!(@strong todo_list_box_rc,
-                                    @strong doing_list_box_rc,
-                                    @strong done_list_box_rc,
-                                    @strong main_window_rc => move |app_state_refresher: Rc<RefCell<AppState>>| {
-        // Clear all list boxes
-        while let Some(child) = todo_list_box_rc.first_child() {
-            todo_list_box_rc.remove(&child);
-        }
-        while let Some(child) = doing_list_box_rc.first_child() {
-            doing_list_box_rc.remove(&child);
-        }
-        while let Some(child) = done_list_box_rc.first_child() {
-            done_list_box_rc.remove(&child);
+    // Checklist progress and the button showing the checklist
+    let progress_label = Label::new(None);
+    progress_label.add_css_class("checklist-progress");
+    if let Some((done, total)) = task.checklist_progress() {
+        progress_label.set_text(&format!("{}/{}", done, total));
+        if done == total {
+            progress_label.add_css_class("checklist-complete");
         }
+    } else {
+        progress_label.set_visible(false);
+    }
 
-        // Re-populate lists based on the updated app_state
-        for t in app_state_refresher.borrow().tasks.iter() {
-            let row_clone = create_task_row(
-                t.clone(),
-                Rc::clone(&app_state_refresher),
-                Rc::clone(&todo_list_box_rc),
-                Rc::clone(&doing_list_box_rc),
-                Rc::clone(&done_list_box_rc),
-                Rc::clone(&main_window_rc)
-            );
-            match t.status {
-                TaskStatus::Todo => todo_list_box_rc.append(&row_clone),
-                TaskStatus::Doing => doing_list_box_rc.append(&row_clone),
-                TaskStatus::Done => done_list_box_rc.append(&row_clone),
-            }
-        }
-    });
+    let repeat_label = Label::new(Some("↻"));
+    repeat_label.add_css_class("repeat-badge");
+    match &task.recurrence {
+        Some(rule) => repeat_label.set_tooltip_text(Some(&format!("Repeats {}", rule))),
+        None => repeat_label.set_visible(false),
+    }
+
+    let expanded = view.expanded_tasks.borrow().contains(&task.id);
+    let expand_button = gtk::ToggleButton::builder()
+        .icon_name("pan-end-symbolic")
+        .active(expanded)
+        .tooltip_text("Checklist")
+        .valign(gtk::Align::Center)
+        .build();
+    expand_button.add_css_class("expand-button");
+
+    let (checklist, checklist_entry) = create_checklist(task, view);
+    let checklist_revealer = gtk::Revealer::builder()
+        .reveal_child(expanded)
+        .child(&checklist)
+        .build();
 
     // Double-click to enable editing using GestureClick
     let gesture = gtk::GestureClick::new();
     gesture.set_button(0);
-    row.add_controller(gesture.clone());
+    hbox.add_controller(gesture.clone());
+
+    // Edit the quick-add form of the task, so tags and the due date survive
+    let edit_text = format_task_description(task);
+    gesture.connect_pressed(clone!(
+        #[weak]
+        view,
+        #[weak]
+        task_entry,
+        move |_, n_press, _, _| {
+            if n_press == 2 {
+                // Check for double click
+                start_task_edit(&view, &task_entry, &edit_text);
+            }
+        }
+    ));
+
+    let task_id = task.id;
 
-    let task_entry_clone = task_entry.clone();
-    gesture.connect_pressed(
-        glib::clone!(@weak task_entry_clone => move |_, n_press, _, _| {
-            if n_press == 2 { // Check for double click
-                task_entry_clone.set_editable(true);
-                task_entry_clone.set_has_frame(true);
-                task_entry_clone.grab_focus();
+    // Drag the row onto another column or another place in its own
+    let drag_source = gtk::DragSource::builder()
+        .actions(gdk::DragAction::MOVE)
+        .propagation_phase(gtk::PropagationPhase::Capture)
+        .build();
+    drag_source.connect_prepare(clone!(
+        #[weak]
+        task_entry,
+        #[upgrade_or]
+        None,
+        move |_, _, _| {
+            // While editing, dragging selects text instead
+            if task_entry.is_editable() {
+                return None;
             }
-        }),
-    );
+            Some(gdk::ContentProvider::for_value(
+                &task_id.to_string().to_value(),
+            ))
+        }
+    ));
+    drag_source.connect_drag_begin(clone!(
+        #[weak]
+        row,
+        move |source, _| {
+            source.set_icon(Some(&gtk::WidgetPaintable::new(Some(&row))), 0, 0);
+        }
+    ));
+    row.add_controller(drag_source);
 
     // Save changes on Enter key press (activate signal)
-    task_entry.connect_activate(glib::clone!(@strong app_state, @strong refresh_ui_for_row, @weak task_entry, @strong task as edit_task => move |_| {
-        let new_full_description = task_entry.text().to_string();
-        if !new_full_description.is_empty() {
-            let (parsed_description, parsed_category, parsed_due_time, parsed_priority) = parse_task_description(&new_full_description);
-
-            app_state.borrow_mut().tasks
-                .iter_mut()
-                .find(|t| t.id == edit_task.id)
-                .map(|t| {
-                    t.description = parsed_description;
-                    t.category = parsed_category;
-                    t.due_time = parsed_due_time;
-                    t.priority = parsed_priority.unwrap_or(t.priority.clone()); // Update priority from parsed text
-                });
-
-            app_state.borrow().save_tasks().expect("Failed to save tasks after editing");
-            refresh_ui_for_row(Rc::clone(&app_state));
-        }
-        task_entry.set_editable(false);
-        task_entry.set_has_frame(false);
-    }));
+    task_entry.connect_activate(clone!(
+        #[weak]
+        view,
+        move |entry_widget| {
+            commit_task_edit(&view, task_id, entry_widget);
+        }
+    ));
 
     // Save changes on focus out
-    task_entry.connect_notify_local(Some("has-focus"), glib::clone!(@strong app_state, @strong refresh_ui_for_row, @weak task_entry, @strong task as edit_task => move |entry_widget, _param_spec| {
-        if !entry_widget.has_focus() && entry_widget.is_editable() { // Check if focus is lost and it was in edit mode
-            let new_full_description = entry_widget.text().to_string();
-            if !new_full_description.is_empty() {
-                let (parsed_description, parsed_category, parsed_due_time, parsed_priority) = parse_task_description(&new_full_description);
-
-                app_state.borrow_mut().tasks
-                    .iter_mut()
-                    .find(|t| t.id == edit_task.id)
-                    .map(|t| {
-                        t.description = parsed_description;
-                        t.category = parsed_category;
-                        t.due_time = parsed_due_time;
-                        t.priority = parsed_priority.unwrap_or(t.priority.clone());
-                    });
-
-                app_state.borrow().save_tasks().expect("Failed to save tasks after editing");
-                refresh_ui_for_row(Rc::clone(&app_state));
-            }
-            entry_widget.set_editable(false);
-            entry_widget.set_has_frame(false);
-        }
-    }));
+    task_entry.connect_notify_local(
+        Some("has-focus"),
+        clone!(
+            #[weak]
+            view,
+            move |entry_widget, _param_spec| {
+                // Check if focus is lost and it was in edit mode
+                if !entry_widget.has_focus() && entry_widget.is_editable() {
+                    commit_task_edit(&view, task_id, entry_widget);
+                }
+            }
+        ),
+    );
 
     // Move Button
-    move_button.connect_clicked(glib::clone!(@strong app_state, @strong refresh_ui_for_row, @strong task as move_task => move |_| {
-        let new_status = match move_task.status {
-            TaskStatus::Todo => TaskStatus::Doing,
-            TaskStatus::Doing => TaskStatus::Done,
-            TaskStatus::Done => TaskStatus::Todo,
-        };
-        app_state.borrow_mut().update_task_status(move_task.id, new_status);
-        refresh_ui_for_row(Rc::clone(&app_state));
-    }));
-
-    // Delete Button
-    delete_button.connect_clicked(glib::clone!(@strong app_state, @strong refresh_ui_for_row, @weak main_window_rc, @strong task as delete_task => move |_| {
-        // Confirmation dialog
-        let dialog = Dialog::with_buttons(
-            Some("Confirm Deletion"),
-            Some(&*main_window_rc),
-            gtk::DialogFlags::MODAL,
-            &[("Delete", ResponseType::Ok), ("Cancel", ResponseType::Cancel)],
-        );
-        dialog.add_css_class("confirm-dialog");
-        dialog.content_area().append(&Label::new(Some("Are you sure you want to delete this task?")));
-        dialog.set_default_response(ResponseType::Cancel);
+    let next_status = next_column.id.clone();
+    move_button.connect_clicked(clone!(
+        #[weak]
+        view,
+        move |_| {
+            view.move_task(task_id, &next_status, None, false);
+        }
+    ));
 
-        dialog.connect_response(glib::clone!(@strong app_state, @strong refresh_ui_for_row, @strong delete_task => move |dialog, response| {
-            if response == ResponseType::Ok {
-                app_state.borrow_mut().delete_task(delete_task.id);
-                refresh_ui_for_row(Rc::clone(&app_state));
+    // Expand Button
+    let expanded_task = task.id;
+    expand_button.connect_toggled(clone!(
+        #[weak]
+        view,
+        #[weak]
+        checklist_revealer,
+        move |button| {
+            let expanded = button.is_active();
+            button.set_icon_name(if expanded {
+                "pan-down-symbolic"
+            } else {
+                "pan-end-symbolic"
+            });
+            checklist_revealer.set_reveal_child(expanded);
+            let mut expanded_tasks = view.expanded_tasks.borrow_mut();
+            if expanded {
+                expanded_tasks.insert(expanded_task);
+            } else {
+                expanded_tasks.remove(&expanded_task);
             }
-            dialog.close();
-        }));
-        dialog.present();
-    }));
+        }
+    ));
+    if expanded {
+        expand_button.set_icon_name("pan-down-symbolic");
+    }
+
+    // Details Button
+    details_button.connect_clicked(clone!(
+        #[weak]
+        view,
+        move |_| show_task_details(&view, task_id)
+    ));
 
+    // Delete Button: deletes straight away and offers to undo
+    delete_button.connect_clicked(clone!(
+        #[weak]
+        view,
+        move |_| view.delete_task(task_id)
+    ));
+
+    hbox.append(&expand_button);
     hbox.append(&task_entry);
+    hbox.append(&progress_label);
+    hbox.append(&repeat_label);
+    for tag in &task.tags {
+        hbox.append(&create_tag_chip(tag, view));
+    }
     hbox.append(&move_button);
+    hbox.append(&details_button);
     hbox.append(&delete_button);
-    row.set_child(Some(&hbox));
+
+    let vbox = Box::new(Orientation::Vertical, 0);
+    vbox.append(&hbox);
+    vbox.append(&checklist_revealer);
+    row.set_child(Some(&vbox));
     row.add_css_class("task-row");
 
+    view.task_rows.borrow_mut().insert(
+        task.id,
+        TaskRow {
+            row: row.clone(),
+            entry: task_entry,
+            checklist_entry,
+        },
+    );
     row
 }
+
+/// Marks the parts of a row's text the search found, given as byte ranges.
+fn search_highlight_attributes(ranges: &[std::ops::Range<usize>]) -> gtk::pango::AttrList {
+    let attributes = gtk::pango::AttrList::new();
+    for range in ranges {
+        let (start, end) = (range.start as u32, range.end as u32);
+        let mut background = gtk::pango::AttrColor::new_background(0xf1f1, 0xc4c4, 0x0f0f);
+        background.set_start_index(start);
+        background.set_end_index(end);
+        attributes.insert(background);
+        let mut foreground = gtk::pango::AttrColor::new_foreground(0x2c2c, 0x3e3e, 0x5050);
+        foreground.set_start_index(start);
+        foreground.set_end_index(end);
+        attributes.insert(foreground);
+    }
+    attributes
+}
+
+/// A tag shown on a task row; clicking it adds the tag to the filter, or
+/// removes it if it is already there.
+fn create_tag_chip(tag: &str, view: &Rc<BoardView>) -> Button {
+    let app_state = view.app_state.borrow();
+    let selected = app_state.filter.tags.iter().any(|t| t == tag);
+    let found = !app_state
+        .filter
+        .search
+        .highlights(&format!("#{}", tag))
+        .is_empty();
+    drop(app_state);
+    let chip = Button::builder()
+        .label(format!("#{}", tag))
+        .tooltip_text(if selected {
+            "Stop filtering by this tag"
+        } else {
+            "Show only tasks with this tag"
+        })
+        .valign(gtk::Align::Center)
+        .build();
+    chip.add_css_class("tag-chip");
+    if found {
+        chip.add_css_class("tag-chip-found");
+    }
+    if selected {
+        chip.add_css_class("tag-chip-selected");
+    }
+    let tag = tag.to_string();
+    chip.connect_clicked(clone!(
+        #[weak]
+        view,
+        move |_| view.toggle_tag_filter(&tag, !selected)
+    ));
+    chip
+}
+
+/// The expandable part of a task row: one check button per checklist item,
+/// each with a remove button, and an entry for adding items, which is
+/// returned as well.
+fn create_checklist(task: &Task, view: &Rc<BoardView>) -> (Box, Entry) {
+    let vbox = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(2)
+        .margin_start(40)
+        .margin_end(10)
+        .margin_bottom(5)
+        .build();
+    vbox.add_css_class("checklist");
+
+    let task_id = task.id;
+    for (index, item) in task.checklist.iter().enumerate() {
+        let item_hbox = Box::new(Orientation::Horizontal, 5);
+
+        let check = gtk::CheckButton::builder()
+            .label(&item.text)
+            .active(item.done)
+            .hexpand(true)
+            .build();
+        check.add_css_class("checklist-item");
+        if item.done {
+            check.add_css_class("completed-task");
+        }
+        check.connect_toggled(clone!(
+            #[weak]
+            view,
+            move |check| {
+                let result = view.app_state.borrow_mut().set_checklist_item_done(
+                    task_id,
+                    index,
+                    check.is_active(),
+                );
+                view.report(result);
+                view.refresh();
+            }
+        ));
+
+        let remove_button = Button::builder()
+            .icon_name("window-close-symbolic")
+            .tooltip_text("Remove item")
+            .build();
+        remove_button.add_css_class("checklist-remove-button");
+        remove_button.connect_clicked(clone!(
+            #[weak]
+            view,
+            move |_| {
+                let result = view
+                    .app_state
+                    .borrow_mut()
+                    .remove_checklist_item(task_id, index);
+                view.report(result);
+                view.refresh();
+            }
+        ));
+
+        item_hbox.append(&check);
+        item_hbox.append(&remove_button);
+        vbox.append(&item_hbox);
+    }
+
+    let new_item_entry = Entry::builder()
+        .placeholder_text("Add a checklist item…")
+        .build();
+    new_item_entry.add_css_class("checklist-entry");
+    new_item_entry.connect_activate(clone!(
+        #[weak]
+        view,
+        move |entry| {
+            let text = entry.text().to_string();
+            if text.trim().is_empty() {
+                return;
+            }
+            let result = view
+                .app_state
+                .borrow_mut()
+                .add_checklist_item(task_id, &text);
+            view.report(result);
+            view.refresh();
+            view.focus_checklist_entry(task_id);
+        }
+    ));
+    vbox.append(&new_item_entry);
+
+    (vbox, new_item_entry)
+}
+
+/// The actions acting on the focused task; see
+/// [`BoardView::update_task_actions`].
+const TASK_ACTIONS: [&str; 5] = [
+    "edit-task",
+    "task-details",
+    "move-task-left",
+    "move-task-right",
+    "delete-task",
+];
+
+/// Registers the actions behind the keyboard shortcuts that are not tied to
+/// a button. The ones acting on the focused task are only enabled while a
+/// task row has the focus.
+fn add_keyboard_actions(
+    app: &Application,
+    view: &Rc<BoardView>,
+    entry: &Entry,
+    clear_filters_button: &Button,
+) {
+    let add_task = gio::SimpleAction::new("add-task", None);
+    add_task.connect_activate(clone!(
+        #[weak]
+        entry,
+        move |_, _| {
+            entry.grab_focus();
+        }
+    ));
+    app.add_action(&add_task);
+
+    let task_action = |name: &str, activate: fn(&Rc<BoardView>, uuid::Uuid)| {
+        let action = gio::SimpleAction::new(name, None);
+        action.set_enabled(false);
+        action.connect_activate(clone!(
+            #[weak]
+            view,
+            move |_, _| {
+                if let Some(task_id) = view.focused_task() {
+                    activate(&view, task_id);
+                }
+            }
+        ));
+        app.add_action(&action);
+    };
+    task_action("edit-task", |view, task_id| view.edit_task(task_id));
+    task_action("task-details", show_task_details);
+    task_action("move-task-left", |view, task_id| {
+        view.move_task_by(task_id, -1)
+    });
+    task_action("move-task-right", |view, task_id| {
+        view.move_task_by(task_id, 1)
+    });
+    task_action("delete-task", |view, task_id| {
+        // Keep the focus in the column, on the row that took its place
+        let row = view.row_for(task_id);
+        let list_box = row.as_ref().and_then(|row| row.parent());
+        view.delete_task(task_id);
+        if let (Some(row), Some(list_box)) = (row, list_box.and_downcast::<ListBox>()) {
+            let next = list_box
+                .row_at_index(row.index())
+                .or_else(|| list_box.row_at_index(row.index() - 1));
+            if let Some(next) = next {
+                next.grab_focus();
+            }
+        }
+    });
+    view.window.connect_focus_widget_notify(clone!(
+        #[weak]
+        view,
+        move |_| view.update_task_actions()
+    ));
+
+    let filter_tags = gio::SimpleAction::new("filter-tags", None);
+    filter_tags.connect_activate(clone!(
+        #[weak]
+        view,
+        move |_, _| view.tag_filter.button.popup()
+    ));
+    app.add_action(&filter_tags);
+
+    let clear_filters = gio::SimpleAction::new("clear-filters", None);
+    clear_filters.connect_activate(clone!(
+        #[weak]
+        clear_filters_button,
+        move |_, _| clear_filters_button.emit_clicked()
+    ));
+    app.add_action(&clear_filters);
+
+    // Switching boards goes through the switcher, which saves the board first
+    let show_board = move |view: &BoardView, pick: &dyn Fn(usize, usize) -> Option<usize>| {
+        let Some(boards) = &view.boards else {
+            return;
+        };
+        let name = {
+            let boards = boards.borrow();
+            let names: Vec<&str> = boards.visible_boards().map(|b| b.name.as_str()).collect();
+            let active = names
+                .iter()
+                .position(|&name| name == boards.active_board().name);
+            active
+                .and_then(|active| pick(active, names.len()))
+                .and_then(|index| names.get(index))
+                .map(|name| name.to_string())
+        };
+        if let Some(name) = name {
+            view.board_switcher.combo.set_active_id(Some(&name));
+        }
+    };
+    let next_board = gio::SimpleAction::new("next-board", None);
+    next_board.connect_activate(clone!(
+        #[weak]
+        view,
+        move |_, _| show_board(&view, &|active, count| Some((active + 1) % count))
+    ));
+    let previous_board = gio::SimpleAction::new("previous-board", None);
+    previous_board.connect_activate(clone!(
+        #[weak]
+        view,
+        move |_, _| show_board(&view, &|active, count| Some((active + count - 1) % count))
+    ));
+    let switch_board = gio::SimpleAction::new("switch-board", Some(glib::VariantTy::INT32));
+    switch_board.connect_activate(clone!(
+        #[weak]
+        view,
+        move |_, parameter| {
+            let Some(number) = parameter.and_then(|p| p.get::<i32>()) else {
+                return;
+            };
+            show_board(&view, &|_, _| usize::try_from(number - 1).ok())
+        }
+    ));
+    for action in [&next_board, &previous_board, &switch_board] {
+        action.set_enabled(view.boards.is_some());
+        app.add_action(action);
+    }
+}
+
+/// Puts a row's entry into edit mode, showing `text`.
+fn start_task_edit(view: &BoardView, task_entry: &Entry, text: &str) {
+    task_entry.set_attributes(&gtk::pango::AttrList::new());
+    task_entry.set_text(text);
+    task_entry.set_editable(true);
+    task_entry.set_has_frame(true);
+    task_entry.grab_focus();
+    // The entry may have had the focus already, so it did not change
+    view.update_task_actions();
+}
+
+/// Re-parses the text of an inline-edited row and leaves edit mode, or
+/// points out a mistake in it and stays in edit mode.
+fn commit_task_edit(view: &Rc<BoardView>, task_id: uuid::Uuid, entry_widget: &Entry) {
+    let new_full_description = entry_widget.text().to_string();
+    if let Err(error) = check_task_description(&new_full_description) {
+        entry_widget.add_css_class("entry-error");
+        entry_widget.set_tooltip_text(Some(&error.to_string()));
+        select_syntax_error(entry_widget, &new_full_description, &error);
+        return;
+    }
+    entry_widget.set_editable(false);
+    entry_widget.set_has_frame(false);
+    view.update_task_actions();
+
+    if !new_full_description.is_empty() {
+        let result = view
+            .app_state
+            .borrow_mut()
+            .update_task_from_text(task_id, &new_full_description);
+        view.report(result);
+        view.refresh();
+    }
+}
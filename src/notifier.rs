@@ -0,0 +1,58 @@
+use chrono::{Local, NaiveDateTime};
+use gtk::prelude::*;
+use gtk::{Application, gio};
+use std::cell::Cell;
+
+use todo_core::AppState;
+
+/// Action a reminder's Snooze button activates, with the task id.
+pub const SNOOZE_ACTION: &str = "snooze-reminder";
+/// Action a reminder's Mark Done button activates, with the task id.
+pub const COMPLETE_ACTION: &str = "complete-task";
+
+/// Sends a desktop notification for each reminder that comes up while the
+/// app is running. Reminders that came up while it was closed are not
+/// sent late.
+pub struct Notifier {
+    app: Application,
+    last_check: Cell<NaiveDateTime>,
+}
+
+impl Notifier {
+    pub fn new(app: &Application) -> Self {
+        Notifier {
+            app: app.clone(),
+            last_check: Cell::new(Local::now().naive_local()),
+        }
+    }
+
+    /// Notifies of the reminders in `app_state` that came up since the last
+    /// check.
+    pub fn check(&self, app_state: &AppState) {
+        let now = Local::now().naive_local();
+        let after = self.last_check.replace(now);
+        for reminder in app_state.reminders_between(after, now) {
+            let notification = gio::Notification::new(&reminder.description);
+            notification.set_body(Some(&reminder.body()));
+            notification.set_priority(gio::NotificationPriority::High);
+            let id = reminder.task_id.to_string();
+            notification.add_button_with_target_value(
+                "Snooze",
+                &format!("app.{}", SNOOZE_ACTION),
+                Some(&id.to_variant()),
+            );
+            notification.add_button_with_target_value(
+                "Mark Done",
+                &format!("app.{}", COMPLETE_ACTION),
+                Some(&id.to_variant()),
+            );
+            // One notification per task; a newer reminder replaces it
+            self.app.send_notification(Some(&id), &notification);
+        }
+    }
+
+    /// Takes the notification for a task off the screen.
+    pub fn withdraw(&self, task_id: uuid::Uuid) {
+        self.app.withdraw_notification(&task_id.to_string());
+    }
+}
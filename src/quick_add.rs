@@ -0,0 +1,334 @@
+use chrono::{Local, NaiveDateTime};
+use glib::clone;
+use gtk::gdk;
+use gtk::glib;
+use gtk::pango::{self, AttrColor, AttrInt, AttrList};
+use gtk::prelude::*;
+use gtk::{
+    Box, Entry, EventControllerFocus, EventControllerKey, Label, ListBox, Orientation, Popover,
+};
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+use std::rc::Rc;
+
+use todo_core::{
+    AppState, Completions, ParsedTask, Priority, SyntaxError, Token, TokenKind, complete_tag,
+    parse_task_description_at, tokenize_task_description,
+};
+
+/// Suggestions shown at once; typing more narrows them down.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Helps with the new-task entry: colours the tags, priority, due date and
+/// repeat rule the parser recognises as they are typed, suggests how to
+/// finish the tag being typed, and previews below the entry how the task
+/// will be saved.
+pub struct QuickAdd {
+    entry: Entry,
+    /// Chips previewing the task; hidden while the entry is empty.
+    pub preview: Box,
+    app_state: Rc<RefCell<AppState>>,
+    popover: Popover,
+    suggestions: ListBox,
+    completions: RefCell<Option<Completions>>,
+    focused: Cell<bool>,
+    /// Due date picked with the calendar button, for tasks whose line has
+    /// none.
+    picked_due: Cell<Option<NaiveDateTime>>,
+}
+
+impl QuickAdd {
+    pub fn new(entry: &Entry, app_state: &Rc<RefCell<AppState>>) -> Rc<Self> {
+        let suggestions = ListBox::builder().can_focus(false).build();
+        suggestions.add_css_class("suggestion-list");
+
+        // Not autohiding, so the entry keeps the keyboard
+        let popover = Popover::builder()
+            .child(&suggestions)
+            .autohide(false)
+            .has_arrow(false)
+            .can_focus(false)
+            .position(gtk::PositionType::Bottom)
+            .halign(gtk::Align::Start)
+            .build();
+        popover.set_parent(entry);
+        entry.connect_destroy(clone!(
+            #[strong]
+            popover,
+            move |_| popover.unparent()
+        ));
+
+        let preview = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(6)
+            .visible(false)
+            .build();
+        preview.add_css_class("quick-add-preview");
+
+        let quick_add = Rc::new(QuickAdd {
+            entry: entry.clone(),
+            preview,
+            app_state: app_state.clone(),
+            popover,
+            suggestions,
+            completions: RefCell::default(),
+            focused: Cell::new(false),
+            picked_due: Cell::new(None),
+        });
+
+        entry.connect_changed(clone!(
+            #[weak]
+            quick_add,
+            move |_| quick_add.update()
+        ));
+        // The cursor moves after the text changes
+        entry.connect_notify_local(
+            Some("cursor-position"),
+            clone!(
+                #[weak]
+                quick_add,
+                move |_, _| quick_add.update_completions()
+            ),
+        );
+
+        let focus = EventControllerFocus::new();
+        focus.connect_enter(clone!(
+            #[weak]
+            quick_add,
+            move |_| quick_add.focused.set(true)
+        ));
+        focus.connect_leave(clone!(
+            #[weak]
+            quick_add,
+            move |_| {
+                quick_add.focused.set(false);
+                quick_add.popover.popdown();
+            }
+        ));
+        entry.add_controller(focus);
+
+        // Before the entry sees the keys, so Enter and Tab pick a suggestion
+        let keys = EventControllerKey::new();
+        keys.set_propagation_phase(gtk::PropagationPhase::Capture);
+        keys.connect_key_pressed(clone!(
+            #[weak]
+            quick_add,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_, key, _, _| quick_add.handle_key(key)
+        ));
+        entry.add_controller(keys);
+
+        quick_add.suggestions.connect_row_activated(clone!(
+            #[weak]
+            quick_add,
+            move |_, row| quick_add.accept(row.index() as usize)
+        ));
+
+        quick_add
+    }
+
+    /// Sets the due date picked with the calendar button.
+    pub fn set_picked_due(&self, due: Option<NaiveDateTime>) {
+        self.picked_due.set(due);
+        self.update();
+    }
+
+    fn update(&self) {
+        let text = self.entry.text();
+        let now = Local::now().naive_local();
+        let default_time = self.app_state.borrow().default_due_time;
+        let (tokens, errors) = tokenize_task_description(&text, now, default_time);
+        self.entry
+            .set_attributes(&highlight_attributes(&tokens, &errors));
+        if text.trim().is_empty() {
+            self.preview.set_visible(false);
+        } else {
+            let parsed = parse_task_description_at(&text, now, default_time);
+            self.show_preview(&parsed, errors.first());
+        }
+        self.update_completions();
+    }
+
+    fn show_preview(&self, parsed: &ParsedTask, error: Option<&SyntaxError>) {
+        while let Some(child) = self.preview.first_child() {
+            self.preview.remove(&child);
+        }
+        let add = |text: &str, class: &str| {
+            let label = Label::builder()
+                .label(text)
+                .ellipsize(pango::EllipsizeMode::End)
+                .build();
+            label.add_css_class(class);
+            self.preview.append(&label);
+        };
+
+        add("Saves as", "preview-caption");
+        if parsed.description.is_empty() {
+            add("No description", "preview-description-missing");
+        } else {
+            add(&parsed.description, "preview-description");
+        }
+        for tag in &parsed.tags {
+            add(&format!("#{}", tag), "tag-chip");
+        }
+        let priority = parsed.priority.clone().unwrap_or_default();
+        add(
+            &format!("{:?}", priority),
+            &format!("priority-{}", format!("{:?}", priority).to_lowercase()),
+        );
+        if let Some(due) = parsed.due_time.or(self.picked_due.get()) {
+            add(
+                &format!("Due {}", due.format("%a %Y-%m-%d %H:%M")),
+                "preview-chip",
+            );
+        }
+        if let Some(rule) = &parsed.recurrence {
+            add(&format!("↻ {}", rule), "preview-chip");
+        }
+        match parsed.checklist.len() {
+            0 => {}
+            1 => add("1 checklist item", "preview-chip"),
+            n => add(&format!("{} checklist items", n), "preview-chip"),
+        }
+        if let Some(error) = error {
+            add(&error.to_string(), "preview-error");
+        }
+        self.preview.set_visible(true);
+    }
+
+    fn update_completions(&self) {
+        let text = self.entry.text();
+        let cursor = text
+            .char_indices()
+            .nth(self.entry.position().max(0) as usize)
+            .map_or(text.len(), |(i, _)| i);
+        let tags = self.app_state.borrow().all_tags();
+        let completions = complete_tag(&text, cursor, &tags).filter(|_| self.focused.get());
+
+        while let Some(child) = self.suggestions.first_child() {
+            self.suggestions.remove(&child);
+        }
+        match &completions {
+            Some(completions) => {
+                for suggestion in completions.suggestions.iter().take(MAX_SUGGESTIONS) {
+                    let hbox = Box::builder()
+                        .orientation(Orientation::Horizontal)
+                        .spacing(12)
+                        .build();
+                    let text = Label::builder()
+                        .label(suggestion.text.as_str())
+                        .halign(gtk::Align::Start)
+                        .hexpand(true)
+                        .build();
+                    text.add_css_class("suggestion-text");
+                    let hint = Label::new(Some(suggestion.hint.as_str()));
+                    hint.add_css_class("suggestion-hint");
+                    hbox.append(&text);
+                    hbox.append(&hint);
+                    self.suggestions.append(&hbox);
+                }
+                self.select(0);
+                self.popover.popup();
+            }
+            None => self.popover.popdown(),
+        }
+        *self.completions.borrow_mut() = completions;
+    }
+
+    fn handle_key(&self, key: gdk::Key) -> glib::Propagation {
+        let count = self
+            .completions
+            .borrow()
+            .as_ref()
+            .map_or(0, |c| c.suggestions.len().min(MAX_SUGGESTIONS));
+        if !self.popover.is_visible() || count == 0 {
+            return glib::Propagation::Proceed;
+        }
+        let selected = self
+            .suggestions
+            .selected_row()
+            .map_or(0, |row| row.index() as usize);
+        match key {
+            gdk::Key::Down => self.select((selected + 1) % count),
+            gdk::Key::Up => self.select((selected + count - 1) % count),
+            gdk::Key::Tab | gdk::Key::Return | gdk::Key::KP_Enter => self.accept(selected),
+            gdk::Key::Escape => self.popover.popdown(),
+            _ => return glib::Propagation::Proceed,
+        }
+        glib::Propagation::Stop
+    }
+
+    fn select(&self, index: usize) {
+        let row = self.suggestions.row_at_index(index as i32);
+        self.suggestions.select_row(row.as_ref());
+    }
+
+    /// Replaces the tag being typed by the suggestion at `index`.
+    fn accept(&self, index: usize) {
+        let Some(completions) = self.completions.borrow_mut().take() else {
+            return;
+        };
+        let Some(suggestion) = completions.suggestions.get(index) else {
+            return;
+        };
+        let text = self.entry.text();
+        let Range { start, end } = completions.span;
+        let spaced = text[end..].starts_with(char::is_whitespace);
+        let start = text[..start].chars().count() as i32;
+        let end = text[..end].chars().count() as i32;
+
+        self.entry.delete_text(start, end);
+        let mut position = start;
+        if spaced {
+            self.entry.insert_text(&suggestion.text, &mut position);
+            position += 1;
+        } else {
+            self.entry
+                .insert_text(&format!("{} ", suggestion.text), &mut position);
+        }
+        self.entry.set_position(position);
+        self.popover.popdown();
+    }
+}
+
+/// Colours the recognised parts of a quick-add line, and underlines its
+/// mistakes.
+fn highlight_attributes(tokens: &[Token], errors: &[SyntaxError]) -> AttrList {
+    let attributes = AttrList::new();
+    let add = |mut attribute: pango::Attribute, span: &Range<usize>| {
+        attribute.set_start_index(span.start as u32);
+        attribute.set_end_index(span.end as u32);
+        attributes.insert(attribute);
+    };
+    for token in tokens {
+        let color = match &token.kind {
+            TokenKind::Word(_) => continue,
+            TokenKind::Tag(_) => 0x5dade2,
+            TokenKind::Priority(Priority::High) => 0xe74c3c,
+            TokenKind::Priority(Priority::Medium) => 0xf39c12,
+            TokenKind::Priority(Priority::Low) => 0x27ae60,
+            TokenKind::Due(_) => 0x1abc9c,
+            TokenKind::Repeat(_) | TokenKind::Until(_) => 0xaf7ac5,
+            TokenKind::ChecklistItem(_) => 0x95a5a6,
+        };
+        add(foreground(color), &token.span);
+        if !matches!(token.kind, TokenKind::ChecklistItem(_)) {
+            add(AttrInt::new_weight(pango::Weight::Bold).into(), &token.span);
+        }
+    }
+    for error in errors {
+        add(foreground(0xe74c3c), &error.span);
+        add(
+            AttrInt::new_underline(pango::Underline::Error).into(),
+            &error.span,
+        );
+    }
+    attributes
+}
+
+/// A text colour given as `0xRRGGBB`.
+fn foreground(rgb: u32) -> pango::Attribute {
+    let channel = |shift: u32| ((rgb >> shift) & 0xff) as u16 * 0x101;
+    AttrColor::new_foreground(channel(16), channel(8), channel(0)).into()
+}
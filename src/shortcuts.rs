@@ -0,0 +1,208 @@
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::{Application, ShortcutsWindow};
+use std::collections::BTreeMap;
+
+/// A line of the shortcuts window.
+struct Shortcut {
+    /// Detailed name of the action the keys activate, or `None` for keys
+    /// handled by the widgets themselves, which cannot be changed.
+    action: Option<&'static str>,
+    /// Default keys, in GTK accelerator syntax.
+    accels: &'static [&'static str],
+    title: &'static str,
+}
+
+const fn action(
+    action: &'static str,
+    accels: &'static [&'static str],
+    title: &'static str,
+) -> Shortcut {
+    Shortcut {
+        action: Some(action),
+        accels,
+        title,
+    }
+}
+
+const fn fixed(accels: &'static [&'static str], title: &'static str) -> Shortcut {
+    Shortcut {
+        action: None,
+        accels,
+        title,
+    }
+}
+
+/// Every shortcut, in the groups the shortcuts window shows.
+const GROUPS: [(&str, &[Shortcut]); 5] = [
+    (
+        "General",
+        &[
+            action("win.command-palette", &["<Control>k"], "Command palette"),
+            action(
+                "win.show-help-overlay",
+                &["<Control>question"],
+                "Keyboard shortcuts",
+            ),
+        ],
+    ),
+    (
+        "Tasks",
+        &[
+            action("app.add-task", &["<Control>n"], "New task"),
+            action("app.edit-task", &["F2"], "Edit the selected task"),
+            action(
+                "app.task-details",
+                &["<Alt>Return"],
+                "Details of the selected task",
+            ),
+            action(
+                "app.move-task-left",
+                &["<Control>Left"],
+                "Move the selected task to the previous column",
+            ),
+            action(
+                "app.move-task-right",
+                &["<Control>Right"],
+                "Move the selected task to the next column",
+            ),
+            action("app.delete-task", &["Delete"], "Delete the selected task"),
+            action("win.undo", &["<Control>z"], "Undo"),
+            action("win.redo", &["<Control><Shift>z", "<Control>y"], "Redo"),
+        ],
+    ),
+    (
+        "Moving around",
+        &[
+            fixed(&["j", "Down"], "Next task in the column"),
+            fixed(&["k", "Up"], "Previous task in the column"),
+            fixed(&["h", "l"], "Previous or next column"),
+            fixed(&["g", "<Shift>g"], "First or last task in the column"),
+        ],
+    ),
+    (
+        "Filtering",
+        &[
+            action("win.find", &["<Control>f"], "Search"),
+            action("app.filter-tags", &["<Control>t"], "Filter by tag"),
+            action("app.clear-filters", &["<Control><Shift>c"], "Clear filters"),
+        ],
+    ),
+    (
+        "Boards",
+        &[
+            action("app.next-board", &["<Control>Page_Down"], "Next board"),
+            action(
+                "app.previous-board",
+                &["<Control>Page_Up"],
+                "Previous board",
+            ),
+            fixed(&["<Alt>1...<Alt>9"], "First to ninth board"),
+        ],
+    ),
+];
+
+/// Sets the keys of every action, the defaults overridden by `overrides`
+/// (see [`Config::shortcuts`](todo_core::Config::shortcuts)). Keys that GTK
+/// cannot read are reported and the default kept.
+pub fn set_accels(app: &Application, overrides: &BTreeMap<String, Vec<String>>) {
+    let shortcuts = GROUPS.iter().flat_map(|(_, shortcuts)| shortcuts.iter());
+    for shortcut in shortcuts {
+        if let Some(action) = shortcut.action {
+            app.set_accels_for_action(action, shortcut.accels);
+        }
+    }
+    for board in 1..=9 {
+        let accel = format!("<Alt>{}", board);
+        app.set_accels_for_action(&format!("app.switch-board({})", board), &[accel.as_str()]);
+    }
+
+    for (action, accels) in overrides {
+        if let Some(accel) = accels
+            .iter()
+            .find(|accel| gtk::accelerator_parse(accel.as_str()).is_none())
+        {
+            eprintln!("Ignoring shortcut for {}: cannot read '{}'", action, accel);
+            continue;
+        }
+        let accels: Vec<&str> = accels.iter().map(String::as_str).collect();
+        app.set_accels_for_action(action, &accels);
+    }
+}
+
+/// The name the shortcuts window gives an action, such as "New task" for
+/// `app.add-task`.
+pub fn title(action: &str) -> Option<&'static str> {
+    GROUPS
+        .iter()
+        .flat_map(|(_, shortcuts)| shortcuts.iter())
+        .find(|shortcut| shortcut.action == Some(action))
+        .map(|shortcut| shortcut.title)
+}
+
+/// The keys set for an action as shown to the user, such as "Ctrl+N".
+pub fn accel_label(app: &Application, action: &str) -> String {
+    let labels: Vec<String> = app
+        .accels_for_action(action)
+        .iter()
+        .filter_map(|accel| gtk::accelerator_parse(accel.as_str()))
+        .map(|(key, modifiers)| gtk::accelerator_get_label(key, modifiers).to_string())
+        .collect();
+    labels.join(", ")
+}
+
+/// A window listing every shortcut with the keys currently set for it.
+pub fn shortcuts_window(app: &Application) -> ShortcutsWindow {
+    let mut groups = String::new();
+    for (title, shortcuts) in GROUPS {
+        let mut lines = String::new();
+        for shortcut in shortcuts {
+            let accels: Vec<String> = match shortcut.action {
+                Some(action) => app
+                    .accels_for_action(action)
+                    .iter()
+                    .map(|accel| accel.to_string())
+                    .collect(),
+                None => shortcut
+                    .accels
+                    .iter()
+                    .map(|accel| accel.to_string())
+                    .collect(),
+            };
+            if accels.is_empty() {
+                continue;
+            }
+            lines.push_str(&format!(
+                r#"<child><object class="GtkShortcutsShortcut">
+                    <property name="title">{}</property>
+                    <property name="accelerator">{}</property>
+                </object></child>"#,
+                glib::markup_escape_text(shortcut.title),
+                glib::markup_escape_text(&accels.join(" ")),
+            ));
+        }
+        groups.push_str(&format!(
+            r#"<child><object class="GtkShortcutsGroup">
+                <property name="title">{}</property>
+                {}
+            </object></child>"#,
+            glib::markup_escape_text(title),
+            lines
+        ));
+    }
+    let ui = format!(
+        r#"<interface>
+            <object class="GtkShortcutsWindow" id="shortcuts">
+                <property name="modal">true</property>
+                <child><object class="GtkShortcutsSection">
+                    <property name="section-name">shortcuts</property>
+                    {}
+                </object></child>
+            </object>
+        </interface>"#,
+        groups
+    );
+    gtk::Builder::from_string(&ui)
+        .object("shortcuts")
+        .expect("the shortcuts window is built above")
+}
@@ -0,0 +1,103 @@
+use gtk::prelude::*;
+use gtk::{Box, CheckButton, Label, MenuButton, Orientation, Popover, Separator};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use todo_core::{TagMatch, TaskFilter};
+
+type ToggledHandler = Rc<RefCell<Option<std::boxed::Box<dyn Fn(&str, bool)>>>>;
+
+/// Filter bar control for picking tags: a button naming the selected tags
+/// that opens a list of every tag on the board, and whether tasks need all
+/// of them or just one.
+pub struct TagFilter {
+    pub button: MenuButton,
+    pub match_any: CheckButton,
+    tags_box: Box,
+    toggled: ToggledHandler,
+}
+
+impl TagFilter {
+    pub fn new() -> Self {
+        let tags_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(2)
+            .build();
+
+        let match_any = CheckButton::builder()
+            .label("Match any selected tag")
+            .tooltip_text("Show tasks with at least one of the tags instead of all of them")
+            .build();
+
+        let vbox = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(5)
+            .build();
+        vbox.append(&tags_box);
+        vbox.append(&Separator::new(Orientation::Horizontal));
+        vbox.append(&match_any);
+
+        let button = MenuButton::builder()
+            .popover(&Popover::builder().child(&vbox).build())
+            .hexpand(true)
+            .build();
+        button.add_css_class("filter-combo");
+
+        TagFilter {
+            button,
+            match_any,
+            tags_box,
+            toggled: Rc::default(),
+        }
+    }
+
+    /// Lists `tags` with the ones in `filter` ticked, and names the selected
+    /// tags on the button. Updating [`match_any`](Self::match_any) runs its
+    /// toggled handlers, so callers must not hold anything those borrow.
+    pub fn set_tags(&self, tags: &[String], filter: &TaskFilter) {
+        while let Some(child) = self.tags_box.first_child() {
+            self.tags_box.remove(&child);
+        }
+        if tags.is_empty() {
+            self.tags_box
+                .append(&Label::new(Some("No tags on this board")));
+        }
+        for tag in tags {
+            let check = CheckButton::builder()
+                .label(format!("#{}", tag))
+                .active(filter.tags.contains(tag))
+                .build();
+            let (tag, toggled) = (tag.clone(), self.toggled.clone());
+            check.connect_toggled(move |check| {
+                if let Some(handler) = toggled.borrow().as_ref() {
+                    handler(&tag, check.is_active());
+                }
+            });
+            self.tags_box.append(&check);
+        }
+
+        let any = filter.tag_match == TagMatch::Any;
+        if self.match_any.is_active() != any {
+            self.match_any.set_active(any);
+        }
+
+        let label = if filter.tags.is_empty() {
+            "All tags".to_string()
+        } else {
+            let joiner = if any { " or " } else { " and " };
+            filter
+                .tags
+                .iter()
+                .map(|tag| format!("#{}", tag))
+                .collect::<Vec<_>>()
+                .join(joiner)
+        };
+        self.button.set_label(&label);
+    }
+
+    /// Calls `handler` with a tag and whether it is now selected whenever
+    /// the user ticks or unticks one.
+    pub fn connect_toggled(&self, handler: impl Fn(&str, bool) + 'static) {
+        *self.toggled.borrow_mut() = Some(std::boxed::Box::new(handler));
+    }
+}
@@ -0,0 +1,78 @@
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::{Box, Button, Label, Orientation, Revealer, RevealerTransitionType};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// How long a toast stays up unless hidden earlier.
+const TOAST_SECONDS: u32 = 5;
+
+/// Short notice floating over the bottom of the board, with one action
+/// button, such as "Task deleted — Undo". It hides itself after a few
+/// seconds.
+pub struct Toast {
+    pub revealer: Revealer,
+    pub action_button: Button,
+    label: Label,
+    timeout: Rc<RefCell<Option<glib::SourceId>>>,
+}
+
+impl Toast {
+    pub fn new(action_label: &str) -> Self {
+        let hbox = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(10)
+            .build();
+        hbox.add_css_class("toast");
+
+        let label = Label::new(None);
+        label.add_css_class("toast-label");
+
+        let action_button = Button::builder().label(action_label).build();
+        action_button.add_css_class("action-button-small");
+
+        hbox.append(&label);
+        hbox.append(&action_button);
+
+        let revealer = Revealer::builder()
+            .transition_type(RevealerTransitionType::SlideUp)
+            .halign(gtk::Align::Center)
+            .valign(gtk::Align::End)
+            .margin_bottom(20)
+            .child(&hbox)
+            .build();
+
+        Toast {
+            revealer,
+            action_button,
+            label,
+            timeout: Rc::default(),
+        }
+    }
+
+    /// Shows `message`, restarting the timer if a toast is already up.
+    pub fn show(&self, message: &str) {
+        self.label.set_text(message);
+        self.revealer.set_reveal_child(true);
+
+        self.cancel_timeout();
+        let revealer = self.revealer.clone();
+        let timeout = self.timeout.clone();
+        let source = glib::timeout_add_seconds_local_once(TOAST_SECONDS, move || {
+            timeout.borrow_mut().take();
+            revealer.set_reveal_child(false);
+        });
+        *self.timeout.borrow_mut() = Some(source);
+    }
+
+    pub fn hide(&self) {
+        self.cancel_timeout();
+        self.revealer.set_reveal_child(false);
+    }
+
+    fn cancel_timeout(&self) {
+        if let Some(source) = self.timeout.borrow_mut().take() {
+            source.remove();
+        }
+    }
+}
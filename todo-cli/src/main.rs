@@ -0,0 +1,700 @@
+//! `todo` — command-line access to the same task file the GUI uses.
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::{DateTime, Local, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use uuid::Uuid;
+
+use todo_core::{
+    AppState, BoardError, BoardRegistry, Column, Config, MoveError, SearchQuery, SortMode,
+    TagMatch, Task, Workflow, WorkflowError, check_task_description, format_minutes,
+    parse_due_filter, paths,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "todo",
+    version,
+    about = "Manage the To-Do board from the command line"
+)]
+struct Cli {
+    /// Task file to operate on instead of a board [default: $TODO_GUI_FILE]
+    #[arg(long, global = true)]
+    file: Option<PathBuf>,
+
+    /// Board to operate on [default: the board used last]
+    #[arg(long, global = true, conflicts_with = "file")]
+    board: Option<String>,
+
+    /// Number of timestamped backups to keep next to the task file [default: from config.json, else 10]
+    #[arg(long, global = true)]
+    backups: Option<usize>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Add a task using the quick-add syntax (e.g. "Ship release #work #p1 #fri_5pm | Tag | Build")
+    Add {
+        #[arg(required = true, num_args = 1..)]
+        text: Vec<String>,
+    },
+    /// List tasks, optionally filtered
+    #[command(alias = "ls")]
+    List {
+        /// Only tasks with this tag; repeat for several
+        #[arg(long = "tag", alias = "category", value_name = "TAG")]
+        tags: Vec<String>,
+        /// With several --tag, list tasks with any of them instead of all
+        #[arg(long)]
+        any: bool,
+        /// Only tasks whose description, tags or notes contain these words;
+        /// quote phrases and put - before words to leave out
+        #[arg(long, short = 's', value_name = "QUERY")]
+        search: Option<String>,
+        /// Only tasks due on this date (YYYY-MM-DD), "none" for tasks without one, or
+        /// "overdue", "today" or "week" for open tasks due by then
+        #[arg(long)]
+        due: Option<String>,
+        /// Only tasks in this column (name or id)
+        #[arg(long)]
+        status: Option<String>,
+        /// Order within each column: priority, due, created, alphabetical or manual
+        /// [default: the board's, as picked in the GUI]
+        #[arg(long, value_name = "ORDER")]
+        sort: Option<String>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+        /// Shorthand for --format json
+        #[arg(long)]
+        json: bool,
+    },
+    /// Move a task to another column or within one; without a column it advances like the GUI button
+    #[command(alias = "mv")]
+    Move {
+        /// Task id or unique id prefix
+        id: String,
+        /// Column name or id
+        status: Option<String>,
+        /// Place the task just above this one (id or unique id prefix) instead of at the bottom
+        #[arg(long)]
+        before: Option<String>,
+        /// Move even if the column's strict work-in-progress limit is reached
+        #[arg(long)]
+        force: bool,
+    },
+    /// Delete a task
+    #[command(alias = "delete")]
+    Rm {
+        /// Task id or unique id prefix
+        id: String,
+    },
+    /// Replace a task's text, re-parsing tags, priority and due time
+    Edit {
+        /// Task id or unique id prefix
+        id: String,
+        #[arg(required = true, num_args = 1..)]
+        text: Vec<String>,
+        /// Store the text as the description verbatim instead of parsing it
+        #[arg(long)]
+        raw: bool,
+    },
+    /// Show every field of a task, including its notes
+    Show {
+        /// Task id or unique id prefix
+        id: String,
+    },
+    /// Print a task's notes, or replace them with the given text
+    Notes {
+        /// Task id or unique id prefix
+        id: String,
+        text: Vec<String>,
+        /// Remove the notes
+        #[arg(long, conflicts_with = "text")]
+        clear: bool,
+    },
+    /// Show when a task is reminded of, or set it in minutes before its
+    /// due time, e.g. `todo remind 3f2a 0 15`
+    Remind {
+        /// Task id or unique id prefix
+        id: String,
+        minutes: Vec<u32>,
+        /// Turn the task's reminders off
+        #[arg(long, conflicts_with_all = ["minutes", "default"])]
+        off: bool,
+        /// Go back to the reminders from config.json
+        #[arg(long, conflicts_with = "minutes")]
+        default: bool,
+    },
+    /// Show a task's checklist, or change it
+    #[command(alias = "cl")]
+    Checklist {
+        /// Task id or unique id prefix
+        id: String,
+        #[command(subcommand)]
+        action: Option<ChecklistCommand>,
+    },
+    /// List the backups kept next to the task file, newest first
+    Backups,
+    /// Replace the task file with one of its backups
+    Restore {
+        /// Backup number as shown by `todo backups` (1 is the newest)
+        index: usize,
+    },
+    /// List the board's columns, or change them
+    Columns {
+        #[command(subcommand)]
+        action: Option<ColumnCommand>,
+    },
+    /// List, create and manage boards
+    Board {
+        #[command(subcommand)]
+        action: BoardCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ChecklistCommand {
+    /// Add an item at the end of the checklist
+    Add {
+        #[arg(required = true, num_args = 1..)]
+        text: Vec<String>,
+    },
+    /// Tick an item (1 is the first)
+    Tick { number: usize },
+    /// Untick an item
+    Untick { number: usize },
+    /// Remove an item
+    Rm { number: usize },
+}
+
+#[derive(Subcommand)]
+enum ColumnCommand {
+    /// Add a column on the right
+    Add {
+        name: String,
+        /// Tasks in this column count as completed
+        #[arg(long)]
+        completed: bool,
+    },
+    /// Rename a column
+    Rename { column: String, new_name: String },
+    /// Remove a column; its tasks move to the first column
+    Remove { column: String },
+    /// Move a column to another place (1 is the leftmost)
+    Move { column: String, position: usize },
+    /// Make tasks in a column count as completed, or not with --off
+    Complete {
+        column: String,
+        #[arg(long)]
+        off: bool,
+    },
+    /// Set a column's work-in-progress limit; without a number the limit is removed
+    Limit {
+        column: String,
+        limit: Option<usize>,
+        /// Refuse moves into the full column unless `todo move --force` is used
+        #[arg(long)]
+        strict: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BoardCommand {
+    /// List boards; the active one is marked with *
+    #[command(alias = "ls")]
+    List {
+        /// Include archived boards
+        #[arg(long)]
+        all: bool,
+    },
+    /// Create an empty board
+    New { name: String },
+    /// Rename a board
+    Rename { name: String, new_name: String },
+    /// Hide a board from the switcher, keeping its tasks
+    Archive { name: String },
+    /// Bring an archived board back
+    Unarchive { name: String },
+    /// Make a board the default for the GUI and later commands
+    Use { name: String },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("todo: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let config = Config::load()?;
+    if let Command::Board { action } = &cli.command {
+        if cli.file.is_some() || paths::data_file_override(None).is_some() {
+            return Err(format!(
+                "boards are not used when a task file is given with --file or ${}",
+                paths::DATA_FILE_ENV
+            )
+            .into());
+        }
+        if cli.board.is_some() {
+            return Err("board commands name their board themselves; leave out --board".into());
+        }
+        let mut boards = BoardRegistry::load(&paths::data_dir_or_cwd())?;
+        return run_board_command(&mut boards, action);
+    }
+
+    let file_path = match (cli.board, paths::data_file_override(cli.file)) {
+        (None, Some(file)) => file,
+        (name, _) => {
+            let boards = BoardRegistry::load(&paths::data_dir_or_cwd())?;
+            let board = match name {
+                Some(name) => boards.find(&name).ok_or(BoardError::NotFound(name))?,
+                None => boards.active_board(),
+            };
+            boards.board_path(board)
+        }
+    };
+    if let Some(legacy) = paths::migrate_legacy_data_file(&file_path)? {
+        eprintln!(
+            "todo: copied {} to {}",
+            legacy.display(),
+            file_path.display()
+        );
+    }
+
+    let mut app_state = AppState::new(file_path);
+    app_state.backup_count = cli.backups.unwrap_or(config.backup_count);
+    app_state.default_due_time = config.default_due_time;
+    app_state.reminder_minutes = config.reminders;
+    app_state.load_tasks()?;
+
+    match cli.command {
+        Command::Add { text } => {
+            let text = text.join(" ");
+            check_task_description(&text)?;
+            let id = app_state.add_task(text)?;
+            println!("{}", id);
+        }
+        Command::List {
+            tags,
+            any,
+            search,
+            due,
+            status,
+            sort,
+            format,
+            json,
+        } => {
+            let column = status
+                .map(|s| resolve_column(&app_state.workflow, &s).map(|c| c.id.clone()))
+                .transpose()?;
+            app_state.filter.tags = tags
+                .iter()
+                .map(|tag| tag.trim_start_matches('#').to_lowercase())
+                .collect();
+            app_state.filter.tag_match = if any { TagMatch::Any } else { TagMatch::All };
+            app_state.filter.due_date = due.map(|d| parse_due_filter(&d)).transpose()?;
+            app_state.filter.search = SearchQuery::parse(search.as_deref().unwrap_or_default());
+            if let Some(sort) = sort {
+                app_state.sort = sort.parse::<SortMode>()?;
+            }
+            let tasks: Vec<&Task> = app_state
+                .filtered_tasks()
+                .filter(|t| column.as_ref().is_none_or(|c| t.status == *c))
+                .collect();
+            if json || format == Format::Json {
+                let out = serde_json::to_string_pretty(&tasks)?;
+                println!("{}", out);
+            } else {
+                print_table(&tasks, &app_state.workflow);
+            }
+        }
+        Command::Move {
+            id,
+            status,
+            before,
+            force,
+        } => {
+            let task = resolve_task(&app_state, &id)?;
+            let before = before
+                .map(|before| resolve_task(&app_state, &before))
+                .transpose()?;
+            // Without a column, --before moves the task into that task's column
+            let workflow = &app_state.workflow;
+            let column = match (status, before) {
+                (Some(status), _) => resolve_column(workflow, &status)?,
+                (None, Some(before)) => resolve_column(workflow, &before.status)?,
+                (None, None) => workflow.next_after(&task.status),
+            };
+            if let Some(before) = before
+                && before.status != column.id
+            {
+                return Err(
+                    format!("task {} is not in the {} column", before.id, column.name).into(),
+                );
+            }
+            let (task_id, column_id, before_id) =
+                (task.id, column.id.clone(), before.map(|b| b.id));
+            app_state
+                .move_task(task_id, &column_id, before_id, force)
+                .map_err(|e| match e {
+                    MoveError::WipLimit { .. } => format!("{} (use --force to move anyway)", e),
+                    e => e.to_string(),
+                })?;
+        }
+        Command::Rm { id } => {
+            let task_id = resolve_task(&app_state, &id)?.id;
+            app_state.delete_task(task_id)?;
+        }
+        Command::Edit { id, text, raw } => {
+            let task_id = resolve_task(&app_state, &id)?.id;
+            let text = text.join(" ");
+            if raw {
+                app_state.update_task_description(task_id, text)?;
+            } else {
+                check_task_description(&text)?;
+                app_state.update_task_from_text(task_id, &text)?;
+            }
+        }
+        Command::Show { id } => {
+            let task = resolve_task(&app_state, &id)?;
+            print_task(task, &app_state.workflow, &app_state.reminder_minutes);
+        }
+        Command::Notes { id, text, clear } => {
+            let task = resolve_task(&app_state, &id)?;
+            if text.is_empty() && !clear {
+                println!("{}", task.notes);
+            } else {
+                let task_id = task.id;
+                app_state.set_task_notes(task_id, &text.join(" "))?;
+            }
+        }
+        Command::Remind {
+            id,
+            minutes,
+            off,
+            default,
+        } => {
+            let task = resolve_task(&app_state, &id)?;
+            let task_id = task.id;
+            if off {
+                app_state.set_task_reminders(task_id, Some(Vec::new()))?;
+            } else if default {
+                app_state.set_task_reminders(task_id, None)?;
+            } else if !minutes.is_empty() {
+                app_state.set_task_reminders(task_id, Some(minutes))?;
+            } else {
+                println!(
+                    "{}",
+                    format_reminders(task.reminders.as_deref(), &app_state.reminder_minutes)
+                );
+            }
+        }
+        Command::Checklist { id, action } => {
+            let task = resolve_task(&app_state, &id)?;
+            let task_id = task.id;
+            let item_index = |number: usize| {
+                number
+                    .checked_sub(1)
+                    .filter(|&i| i < task.checklist.len())
+                    .ok_or_else(|| format!("task {} has no checklist item {}", task_id, number))
+            };
+            match action {
+                None => {
+                    for (i, item) in task.checklist.iter().enumerate() {
+                        let mark = if item.done { 'x' } else { ' ' };
+                        println!("{:>3}  [{}] {}", i + 1, mark, item.text);
+                    }
+                }
+                Some(ChecklistCommand::Add { text }) => {
+                    app_state.add_checklist_item(task_id, &text.join(" "))?;
+                }
+                Some(ChecklistCommand::Tick { number }) => {
+                    let index = item_index(number)?;
+                    app_state.set_checklist_item_done(task_id, index, true)?;
+                }
+                Some(ChecklistCommand::Untick { number }) => {
+                    let index = item_index(number)?;
+                    app_state.set_checklist_item_done(task_id, index, false)?;
+                }
+                Some(ChecklistCommand::Rm { number }) => {
+                    let index = item_index(number)?;
+                    app_state.remove_checklist_item(task_id, index)?;
+                }
+            }
+        }
+        Command::Backups => {
+            let backups = app_state.list_backups()?;
+            for (i, backup) in backups.iter().enumerate() {
+                println!(
+                    "{:>3}  {}  {}",
+                    i + 1,
+                    backup.created.format("%Y-%m-%d %H:%M:%S"),
+                    backup.path.display()
+                );
+            }
+        }
+        Command::Restore { index } => {
+            let backups = app_state.list_backups()?;
+            let backup = index
+                .checked_sub(1)
+                .and_then(|i| backups.get(i))
+                .ok_or_else(|| format!("no backup number {}", index))?;
+            app_state.restore_backup(&backup.path)?;
+        }
+        Command::Columns { action } => {
+            let mut workflow = app_state.workflow.clone();
+            match action {
+                None => {
+                    for (i, column) in workflow.columns.iter().enumerate() {
+                        let count = app_state.count_in_column(&column.id);
+                        let mut notes = String::new();
+                        if let Some(limit) = column.wip_limit {
+                            let strict = if column.strict_limit { ", strict" } else { "" };
+                            notes.push_str(&format!("  (limit {}{})", limit, strict));
+                        }
+                        if column.over_limit(count) {
+                            notes.push_str("  OVER LIMIT");
+                        }
+                        if column.completed {
+                            notes.push_str("  (completed)");
+                        }
+                        println!(
+                            "{:>3}  {}  [{}]  {} tasks{}",
+                            i + 1,
+                            column.name,
+                            column.id,
+                            count,
+                            notes
+                        );
+                    }
+                    return Ok(());
+                }
+                Some(ColumnCommand::Add { name, completed }) => {
+                    let id = workflow.add_column(&name)?.id.clone();
+                    workflow.set_completed(&id, completed)?;
+                }
+                Some(ColumnCommand::Rename { column, new_name }) => {
+                    let id = resolve_column(&workflow, &column)?.id.clone();
+                    workflow.rename_column(&id, &new_name)?;
+                }
+                Some(ColumnCommand::Remove { column }) => {
+                    let id = resolve_column(&workflow, &column)?.id.clone();
+                    workflow.remove_column(&id)?;
+                }
+                Some(ColumnCommand::Move { column, position }) => {
+                    let id = resolve_column(&workflow, &column)?.id.clone();
+                    workflow.move_column(&id, position.saturating_sub(1))?;
+                }
+                Some(ColumnCommand::Complete { column, off }) => {
+                    let id = resolve_column(&workflow, &column)?.id.clone();
+                    workflow.set_completed(&id, !off)?;
+                }
+                Some(ColumnCommand::Limit {
+                    column,
+                    limit,
+                    strict,
+                }) => {
+                    let id = resolve_column(&workflow, &column)?.id.clone();
+                    workflow.set_wip_limit(&id, limit.filter(|&l| l > 0), strict)?;
+                }
+            }
+            app_state.set_workflow(workflow)?;
+        }
+        Command::Board { .. } => unreachable!("board commands are handled above"),
+    }
+    Ok(())
+}
+
+fn run_board_command(
+    boards: &mut BoardRegistry,
+    action: &BoardCommand,
+) -> Result<(), Box<dyn Error>> {
+    match action {
+        BoardCommand::List { all } => {
+            let active = boards.active_board().name.clone();
+            for board in boards.boards.iter().filter(|b| *all || !b.archived) {
+                let marker = if board.name == active { '*' } else { ' ' };
+                let archived = if board.archived { "  (archived)" } else { "" };
+                println!(
+                    "{} {}  {}{}",
+                    marker,
+                    board.name,
+                    boards.board_path(board).display(),
+                    archived
+                );
+            }
+        }
+        BoardCommand::New { name } => {
+            let board = boards.create(name)?.clone();
+            println!("{}", boards.board_path(&board).display());
+        }
+        BoardCommand::Rename { name, new_name } => boards.rename(name, new_name)?,
+        BoardCommand::Archive { name } => boards.archive(name)?,
+        BoardCommand::Unarchive { name } => boards.unarchive(name)?,
+        BoardCommand::Use { name } => boards.set_active(name)?,
+    }
+    Ok(())
+}
+
+/// Looks a column up by id or name.
+fn resolve_column<'a>(workflow: &'a Workflow, name: &str) -> Result<&'a Column, WorkflowError> {
+    workflow
+        .find(name)
+        .ok_or_else(|| WorkflowError::NotFound(name.to_string()))
+}
+
+/// Looks a task up by full id or by an unambiguous id prefix.
+fn resolve_task<'a>(app_state: &'a AppState, id: &str) -> Result<&'a Task, String> {
+    if let Ok(uuid) = Uuid::parse_str(id) {
+        return app_state
+            .find_task(uuid)
+            .ok_or_else(|| format!("no task with id {}", id));
+    }
+
+    let prefix = id.to_lowercase();
+    let mut matches = app_state
+        .tasks
+        .iter()
+        .filter(|t| t.id.to_string().starts_with(&prefix));
+    match (matches.next(), matches.next()) {
+        (Some(task), None) => Ok(task),
+        (None, _) => Err(format!("no task with id starting with '{}'", id)),
+        (Some(_), Some(_)) => Err(format!("id prefix '{}' matches several tasks", id)),
+    }
+}
+
+fn print_task(task: &Task, workflow: &Workflow, reminder_minutes: &[u32]) {
+    let format_time = |time: Option<DateTime<Utc>>| {
+        time.map_or_else(
+            || "unknown".to_string(),
+            |time| {
+                time.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            },
+        )
+    };
+    let column = workflow
+        .column(&task.status)
+        .map_or(task.status.as_str(), |c| c.name.as_str());
+    println!("ID           {}", task.id);
+    println!("Description  {}", task.description);
+    println!("Column       {}", column);
+    println!("Priority     {:?}", task.priority);
+    if let Some(due_time) = task.due_time {
+        println!("Due          {}", due_time.format("%Y-%m-%d %H:%M"));
+    }
+    if task.due_time.is_some() {
+        println!(
+            "Reminders    {}",
+            format_reminders(task.reminders.as_deref(), reminder_minutes)
+        );
+    }
+    if let Some(recurrence) = &task.recurrence {
+        println!("Repeats      {} ({})", recurrence, recurrence.to_rrule());
+    }
+    if !task.tags.is_empty() {
+        println!("Tags         {}", format_tags(&task.tags));
+    }
+    println!("Created      {}", format_time(task.created_at));
+    println!("Updated      {}", format_time(task.updated_at));
+    if let Some((done, total)) = task.checklist_progress() {
+        println!("Checklist    {}/{}", done, total);
+        for item in &task.checklist {
+            println!("  [{}] {}", if item.done { 'x' } else { ' ' }, item.text);
+        }
+    }
+    if !task.notes.is_empty() {
+        println!();
+        println!("{}", task.notes);
+    }
+}
+
+/// A task's reminders, or the configured ones when it has none of its own.
+fn format_reminders(reminders: Option<&[u32]>, defaults: &[u32]) -> String {
+    let minutes = reminders.unwrap_or(defaults);
+    if minutes.is_empty() {
+        return "off".to_string();
+    }
+    let times: Vec<String> = minutes
+        .iter()
+        .map(|&minutes| match minutes {
+            0 => "at the due time".to_string(),
+            minutes => format!("{} before", format_minutes(minutes)),
+        })
+        .collect();
+    let source = if reminders.is_some() {
+        ""
+    } else {
+        " (default)"
+    };
+    format!("{}{}", times.join(", "), source)
+}
+
+fn format_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| format!("#{}", tag))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn print_table(tasks: &[&Task], workflow: &Workflow) {
+    let rows: Vec<[String; 6]> = tasks
+        .iter()
+        .map(|t| {
+            [
+                t.id.to_string()[..8].to_string(),
+                workflow
+                    .column(&t.status)
+                    .map_or_else(|| t.status.clone(), |c| c.name.clone()),
+                format!("{:?}", t.priority),
+                t.due_time
+                    .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default(),
+                format_tags(&t.tags),
+                match t.checklist_progress() {
+                    Some((done, total)) => format!("{} [{}/{}]", t.description, done, total),
+                    None => t.description.clone(),
+                },
+            ]
+        })
+        .collect();
+
+    let header = ["ID", "COLUMN", "PRIORITY", "DUE", "TAGS", "DESCRIPTION"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |cells: [&str; 6]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+    print_row(header);
+    for row in &rows {
+        print_row(row.each_ref().map(String::as_str));
+    }
+}
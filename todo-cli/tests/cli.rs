@@ -0,0 +1,188 @@
+//! Runs the `todo` binary against task files in a temporary directory.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde_json::Value;
+
+/// A temporary home for one test, removed when dropped. Commands run in it
+/// with the XDG directories pointing inside it.
+struct Home(PathBuf);
+
+impl Home {
+    fn new() -> Self {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "todo-cli-test-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&path).unwrap();
+        Home(path)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Runs `todo` with `args`.
+    fn run(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_todo"))
+            .args(args)
+            .current_dir(&self.0)
+            .env("HOME", &self.0)
+            .env("XDG_DATA_HOME", self.0.join("data"))
+            .env("XDG_CONFIG_HOME", self.0.join("config"))
+            .env_remove("TODO_GUI_FILE")
+            .output()
+            .unwrap()
+    }
+
+    /// Runs `todo --file tasks.json` with `args`, which must succeed, and
+    /// returns what it printed.
+    fn todo(&self, args: &[&str]) -> String {
+        let output = self.run(&[&["--file", "tasks.json"], args].concat());
+        assert!(
+            output.status.success(),
+            "todo {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    /// Adds a task, returning its id.
+    fn add(&self, text: &str) -> String {
+        self.todo(&["add", text]).trim().to_string()
+    }
+
+    /// The tasks listed with `args` as JSON.
+    fn list(&self, args: &[&str]) -> Vec<Value> {
+        let json = self.todo(&[&["list", "--json"], args].concat());
+        match serde_json::from_str(&json).unwrap() {
+            Value::Array(tasks) => tasks,
+            other => panic!("expected a task list, got {}", other),
+        }
+    }
+}
+
+impl Drop for Home {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn descriptions(tasks: &[Value]) -> Vec<&str> {
+    tasks
+        .iter()
+        .map(|task| task["description"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn added_tasks_are_listed() {
+    let home = Home::new();
+    let id = home.add("Buy milk #home #p1");
+    home.add("Call Ana");
+    assert!(home.path().join("tasks.json").is_file());
+
+    let tasks = home.list(&[]);
+    assert_eq!(descriptions(&tasks), ["Buy milk", "Call Ana"]);
+    assert_eq!(tasks[0]["id"], id.as_str());
+    assert_eq!(tasks[0]["tags"], serde_json::json!(["home"]));
+    assert_eq!(tasks[0]["priority"], "High");
+    assert_eq!(tasks[0]["status"], "todo");
+    assert_eq!(descriptions(&home.list(&["--tag", "home"])), ["Buy milk"]);
+
+    let table = home.todo(&["list"]);
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("ID"));
+    assert!(lines[1].starts_with(&id[..8]));
+    assert!(lines[1].contains("To Do") && lines[1].ends_with("Buy milk"));
+}
+
+#[test]
+fn moved_tasks_change_column_and_order() {
+    let home = Home::new();
+    let milk = home.add("Buy milk");
+    let ana = home.add("Call Ana");
+
+    // Without a column a task moves on to the next one
+    home.todo(&["move", &milk[..8]]);
+    home.todo(&["move", &ana, "Doing", "--before", &milk]);
+    assert_eq!(
+        descriptions(&home.list(&["--status", "doing"])),
+        ["Call Ana", "Buy milk"]
+    );
+
+    home.todo(&["move", &milk, "done"]);
+    assert_eq!(
+        descriptions(&home.list(&["--status", "Done"])),
+        ["Buy milk"]
+    );
+}
+
+#[test]
+fn edited_tasks_are_parsed_again() {
+    let home = Home::new();
+    let id = home.add("Buy milk #home #p1");
+    home.todo(&["edit", &id, "Buy oat milk #shop"]);
+    let tasks = home.list(&[]);
+    assert_eq!(descriptions(&tasks), ["Buy oat milk"]);
+    assert_eq!(tasks[0]["tags"], serde_json::json!(["shop"]));
+    assert_eq!(tasks[0]["priority"], "Medium");
+
+    home.todo(&["edit", "--raw", &id, "Buy #milk"]);
+    assert_eq!(descriptions(&home.list(&[])), ["Buy #milk"]);
+}
+
+#[test]
+fn removed_tasks_are_gone() {
+    let home = Home::new();
+    let id = home.add("Buy milk");
+    home.add("Call Ana");
+    home.todo(&["rm", &id]);
+    assert_eq!(descriptions(&home.list(&[])), ["Call Ana"]);
+}
+
+#[test]
+fn unknown_tasks_are_an_error() {
+    let home = Home::new();
+    home.add("Buy milk");
+    for args in [
+        ["rm", "zzzz"],
+        ["show", "00000000-0000-0000-0000-000000000000"],
+    ] {
+        let output = home.run(&[&["--file", "tasks.json"], &args[..]].concat());
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.starts_with("todo: no task with id"), "{}", stderr);
+    }
+    assert_eq!(home.list(&[]).len(), 1);
+}
+
+#[test]
+fn boards_hold_their_own_tasks() {
+    let home = Home::new();
+    let ok = |args: &[&str]| {
+        let output = home.run(args);
+        assert!(output.status.success(), "todo {:?} failed", args);
+    };
+    ok(&["board", "new", "Work"]);
+    ok(&["--board", "work", "add", "Write report"]);
+    ok(&["add", "Buy milk"]);
+    assert!(home.path().join("data/todo-gui/boards/work.json").is_file());
+
+    // Board commands name their board, so --board is refused
+    let output = home.run(&["--board", "Work", "board", "archive", "Default"]);
+    assert!(!output.status.success());
+    let output = home.run(&["--file", "tasks.json", "board", "list"]);
+    assert!(!output.status.success());
+
+    ok(&["board", "use", "Work"]);
+    let output = home.run(&["list", "--json"]);
+    let tasks: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(descriptions(&tasks), ["Write report"]);
+}
@@ -0,0 +1,462 @@
+//! Named boards, each stored in its own task file.
+//!
+//! The list of boards lives in `boards.json` in the data directory. Before it
+//! exists there is a single board, "Default", backed by the task file older
+//! versions used, so existing data shows up as that board.
+
+use std::fmt;
+use std::fs;
+use std::io::BufReader;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::StorageError;
+use crate::paths::DATA_FILE_NAME;
+use crate::storage::write_json_atomic;
+
+/// Name of the board list inside the data directory.
+pub const BOARDS_FILE_NAME: &str = "boards.json";
+/// Name of the board that exists before any other is created.
+pub const DEFAULT_BOARD_NAME: &str = "Default";
+
+const BOARDS_SUBDIR: &str = "boards";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Board {
+    pub name: String,
+    /// Task file, relative to the directory holding `boards.json`.
+    pub file: PathBuf,
+    /// Archived boards keep their tasks but are hidden from the switcher.
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// Why a board operation was refused.
+#[derive(Debug)]
+pub enum BoardError {
+    NotFound(String),
+    AlreadyExists(String),
+    EmptyName,
+    /// Archiving would leave no board to show.
+    LastBoard(String),
+    Storage(StorageError),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::NotFound(name) => write!(f, "there is no board named '{}'", name),
+            BoardError::AlreadyExists(name) => write!(f, "a board named '{}' already exists", name),
+            BoardError::EmptyName => write!(f, "board names cannot be empty"),
+            BoardError::LastBoard(name) => {
+                write!(
+                    f,
+                    "'{}' is the only board left and cannot be archived",
+                    name
+                )
+            }
+            BoardError::Storage(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BoardError::Storage(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<StorageError> for BoardError {
+    fn from(e: StorageError) -> Self {
+        BoardError::Storage(e)
+    }
+}
+
+/// The boards known in a data directory and which one was used last.
+///
+/// Board names are matched case-insensitively. Every change is written back
+/// to `boards.json` straight away.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardRegistry {
+    pub boards: Vec<Board>,
+    pub active: String,
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+impl BoardRegistry {
+    /// Reads `boards.json` from `dir`, or starts with just the default board.
+    /// A file without any board, or with a task file outside `dir`, is
+    /// malformed.
+    pub fn load(dir: &Path) -> Result<BoardRegistry, StorageError> {
+        let path = dir.join(BOARDS_FILE_NAME);
+        let mut registry = if path.exists() {
+            let file = fs::File::open(&path).map_err(StorageError::io(&path))?;
+            let registry: BoardRegistry = serde_json::from_reader(BufReader::new(file))
+                .map_err(StorageError::parse(&path))?;
+            if registry.boards.is_empty() {
+                return Err(StorageError::malformed(&path)(
+                    "the board list is empty".to_string(),
+                ));
+            }
+            if let Some(board) = registry.boards.iter().find(|b| !is_inside_dir(&b.file)) {
+                return Err(StorageError::malformed(&path)(format!(
+                    "the task file of board '{}' is outside the data directory",
+                    board.name
+                )));
+            }
+            registry
+        } else {
+            BoardRegistry {
+                boards: vec![Board {
+                    name: DEFAULT_BOARD_NAME.to_string(),
+                    file: PathBuf::from(DATA_FILE_NAME),
+                    archived: false,
+                }],
+                active: DEFAULT_BOARD_NAME.to_string(),
+                dir: PathBuf::new(),
+            }
+        };
+        registry.dir = dir.to_path_buf();
+        Ok(registry)
+    }
+
+    pub fn save(&self) -> Result<(), StorageError> {
+        write_json_atomic(&self.dir.join(BOARDS_FILE_NAME), self, || Ok(()))
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Board> {
+        self.boards
+            .iter()
+            .find(|b| b.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    fn find_mut(&mut self, name: &str) -> Result<&mut Board, BoardError> {
+        self.boards
+            .iter_mut()
+            .find(|b| b.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| BoardError::NotFound(name.to_string()))
+    }
+
+    /// Boards that are not archived, in creation order.
+    pub fn visible_boards(&self) -> impl Iterator<Item = &Board> {
+        self.boards.iter().filter(|b| !b.archived)
+    }
+
+    /// The board used last, falling back to the first visible one.
+    pub fn active_board(&self) -> &Board {
+        self.find(&self.active)
+            .filter(|b| !b.archived)
+            .or_else(|| self.visible_boards().next())
+            .unwrap_or(&self.boards[0])
+    }
+
+    /// The task file of `board`.
+    pub fn board_path(&self, board: &Board) -> PathBuf {
+        self.dir.join(&board.file)
+    }
+
+    pub fn set_active(&mut self, name: &str) -> Result<(), BoardError> {
+        self.active = self
+            .find(name)
+            .ok_or_else(|| BoardError::NotFound(name.to_string()))?
+            .name
+            .clone();
+        self.save()?;
+        Ok(())
+    }
+
+    /// Adds an empty board with its own task file.
+    pub fn create(&mut self, name: &str) -> Result<&Board, BoardError> {
+        let name = self.check_new_name(name)?;
+        let slug = match slugify(&name) {
+            slug if slug.is_empty() => "board".to_string(),
+            slug => slug,
+        };
+        let mut file = PathBuf::from(BOARDS_SUBDIR).join(format!("{}.json", slug));
+        let mut n = 2;
+        while self.boards.iter().any(|b| b.file == file) || self.dir.join(&file).exists() {
+            file = PathBuf::from(BOARDS_SUBDIR).join(format!("{}-{}.json", slug, n));
+            n += 1;
+        }
+
+        self.boards.push(Board {
+            name,
+            file,
+            archived: false,
+        });
+        self.save()?;
+        Ok(self.boards.last().expect("board was just added"))
+    }
+
+    /// Changes a board's name. Its task file stays where it is.
+    pub fn rename(&mut self, name: &str, new_name: &str) -> Result<(), BoardError> {
+        let new_name = match self.find(new_name) {
+            // Allow changing only the capitalisation of a name
+            Some(existing) if existing.name.eq_ignore_ascii_case(name.trim()) => {
+                new_name.trim().to_string()
+            }
+            _ => self.check_new_name(new_name)?,
+        };
+        let was_active = self.active.eq_ignore_ascii_case(name.trim());
+        self.find_mut(name)?.name = new_name.clone();
+        if was_active {
+            self.active = new_name;
+        }
+        self.save()?;
+        Ok(())
+    }
+
+    /// Hides a board from the switcher. If it was the active board, the
+    /// first remaining board becomes active.
+    pub fn archive(&mut self, name: &str) -> Result<(), BoardError> {
+        let board_name = self
+            .find(name)
+            .ok_or_else(|| BoardError::NotFound(name.to_string()))?
+            .name
+            .clone();
+        if self.visible_boards().all(|b| b.name == board_name) {
+            return Err(BoardError::LastBoard(board_name));
+        }
+        self.find_mut(name)?.archived = true;
+        if self.active == board_name {
+            self.active = self.active_board().name.clone();
+        }
+        self.save()?;
+        Ok(())
+    }
+
+    pub fn unarchive(&mut self, name: &str) -> Result<(), BoardError> {
+        self.find_mut(name)?.archived = false;
+        self.save()?;
+        Ok(())
+    }
+
+    fn check_new_name(&self, name: &str) -> Result<String, BoardError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(BoardError::EmptyName);
+        }
+        if let Some(existing) = self.find(name) {
+            return Err(BoardError::AlreadyExists(existing.name.clone()));
+        }
+        Ok(name.to_string())
+    }
+}
+
+/// Whether `file` is a relative path that stays inside the directory it is
+/// relative to.
+fn is_inside_dir(file: &Path) -> bool {
+    file.components().next().is_some()
+        && file
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Turns a name into lowercase words joined by `-`, safe to use in file
+/// names and ids. Empty if the name has no letters or digits.
+pub(crate) fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn an_empty_board_list_is_malformed() {
+        let dir = TempDir::new();
+        assert_eq!(
+            BoardRegistry::load(dir.path()).unwrap().active_board().name,
+            DEFAULT_BOARD_NAME
+        );
+        fs::write(
+            dir.path().join(BOARDS_FILE_NAME),
+            r#"{ "boards": [], "active": "Tasks" }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            BoardRegistry::load(dir.path()),
+            Err(StorageError::Malformed { .. })
+        ));
+    }
+
+    #[test]
+    fn board_files_outside_the_directory_are_malformed() {
+        let dir = TempDir::new();
+        for file in [
+            "../tasks.json",
+            "boards/../../tasks.json",
+            "/tmp/tasks.json",
+            "",
+        ] {
+            let json = format!(
+                r#"{{ "boards": [{{ "name": "Tasks", "file": "{}" }}], "active": "Tasks" }}"#,
+                file
+            );
+            fs::write(dir.path().join(BOARDS_FILE_NAME), json).unwrap();
+            assert!(
+                matches!(
+                    BoardRegistry::load(dir.path()),
+                    Err(StorageError::Malformed { .. })
+                ),
+                "{}",
+                file
+            );
+        }
+
+        fs::write(
+            dir.path().join(BOARDS_FILE_NAME),
+            r#"{ "boards": [{ "name": "Tasks", "file": "boards/tasks.json" }], "active": "Tasks" }"#,
+        )
+        .unwrap();
+        let registry = BoardRegistry::load(dir.path()).unwrap();
+        assert_eq!(
+            registry.board_path(registry.active_board()),
+            dir.path().join("boards/tasks.json")
+        );
+    }
+
+    /// Each board as `(name, file, archived)`.
+    fn boards(registry: &BoardRegistry) -> Vec<(&str, &str, bool)> {
+        registry
+            .boards
+            .iter()
+            .map(|b| (b.name.as_str(), b.file.to_str().unwrap(), b.archived))
+            .collect()
+    }
+
+    #[test]
+    fn created_boards_get_their_own_file() {
+        let dir = TempDir::new();
+        let mut registry = BoardRegistry::load(dir.path()).unwrap();
+        registry.create("Home Jobs").unwrap();
+        // Names differing only in case are taken; slugs that are taken get
+        // a number
+        assert!(matches!(
+            registry.create(" home jobs "),
+            Err(BoardError::AlreadyExists(name)) if name == "Home Jobs"
+        ));
+        registry.create("Home: jobs!").unwrap();
+        // A file left behind by another board is not reused
+        fs::create_dir_all(dir.path().join("boards")).unwrap();
+        fs::write(dir.path().join("boards/home-jobs-3.json"), "{}").unwrap();
+        registry.create("Home jobs?").unwrap();
+        registry.create("???").unwrap();
+        assert!(matches!(registry.create("  "), Err(BoardError::EmptyName)));
+
+        assert_eq!(
+            boards(&registry),
+            [
+                ("Default", DATA_FILE_NAME, false),
+                ("Home Jobs", "boards/home-jobs.json", false),
+                ("Home: jobs!", "boards/home-jobs-2.json", false),
+                ("Home jobs?", "boards/home-jobs-4.json", false),
+                ("???", "boards/board.json", false),
+            ]
+        );
+        let reloaded = BoardRegistry::load(dir.path()).unwrap();
+        assert_eq!(boards(&reloaded), boards(&registry));
+    }
+
+    #[test]
+    fn renaming_keeps_the_file_and_the_active_board() {
+        let dir = TempDir::new();
+        let mut registry = BoardRegistry::load(dir.path()).unwrap();
+        registry.create("Work").unwrap();
+        registry.set_active("work").unwrap();
+        assert_eq!(registry.active, "Work");
+
+        // Changing only the case is allowed
+        registry.rename("work", "WORK").unwrap();
+        assert_eq!(registry.active, "WORK");
+        registry.rename("WORK", "Office").unwrap();
+        assert_eq!(registry.active, "Office");
+        assert_eq!(
+            registry.find("office").unwrap().file,
+            Path::new("boards/work.json")
+        );
+
+        assert!(matches!(
+            registry.rename("Office", "default"),
+            Err(BoardError::AlreadyExists(name)) if name == "Default"
+        ));
+        assert!(matches!(
+            registry.rename("Home", "House"),
+            Err(BoardError::NotFound(_))
+        ));
+        // Renaming another board leaves the active one alone
+        registry.rename("Default", "Personal").unwrap();
+        assert_eq!(registry.active, "Office");
+
+        let reloaded = BoardRegistry::load(dir.path()).unwrap();
+        assert_eq!(reloaded.active, "Office");
+        assert_eq!(boards(&reloaded), boards(&registry));
+    }
+
+    #[test]
+    fn archiving_hides_boards_but_keeps_one() {
+        let dir = TempDir::new();
+        let mut registry = BoardRegistry::load(dir.path()).unwrap();
+        assert!(matches!(
+            registry.archive("default"),
+            Err(BoardError::LastBoard(name)) if name == "Default"
+        ));
+
+        registry.create("Work").unwrap();
+        registry.create("Home").unwrap();
+        registry.set_active("Home").unwrap();
+        // Archiving the active board makes the first remaining one active
+        registry.archive("home").unwrap();
+        assert_eq!(registry.active, "Default");
+        registry.archive("Default").unwrap();
+        assert_eq!(registry.active, "Work");
+        assert!(matches!(
+            registry.archive("Work"),
+            Err(BoardError::LastBoard(_))
+        ));
+        let visible: Vec<&str> = registry.visible_boards().map(|b| b.name.as_str()).collect();
+        assert_eq!(visible, ["Work"]);
+
+        // An archived board can still be named as the active one, but is
+        // not shown
+        registry.active = "Home".to_string();
+        assert_eq!(registry.active_board().name, "Work");
+
+        registry.unarchive("HOME").unwrap();
+        assert_eq!(registry.active_board().name, "Home");
+        let reloaded = BoardRegistry::load(dir.path()).unwrap();
+        assert_eq!(boards(&reloaded), boards(&registry));
+        assert!(matches!(
+            registry.unarchive("Garden"),
+            Err(BoardError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn the_active_board_is_saved() {
+        let dir = TempDir::new();
+        let mut registry = BoardRegistry::load(dir.path()).unwrap();
+        registry.create("Work").unwrap();
+        registry.set_active(" WORK ").unwrap();
+        assert_eq!(registry.active, "Work");
+        assert!(matches!(
+            registry.set_active("Home"),
+            Err(BoardError::NotFound(_))
+        ));
+        assert_eq!(BoardRegistry::load(dir.path()).unwrap().active, "Work");
+    }
+}
@@ -0,0 +1,169 @@
+//! Suggestions for the tag being typed in the quick-add line.
+
+use std::ops::Range;
+
+use crate::parser::split_unescaped;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionKind {
+    Tag,
+    Priority,
+    DueDate,
+    Repeat,
+}
+
+/// A tag that finishes the one being typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// The whole tag, with its `#` or `@`.
+    pub text: String,
+    pub kind: SuggestionKind,
+    /// What the tag does, such as "Due tomorrow".
+    pub hint: String,
+}
+
+/// The tag being typed and what it could be finished with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completions {
+    /// Byte range of the tag in the line, to be replaced by a suggestion.
+    pub span: Range<usize>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// Priorities, due dates and repeat rules, in the order they are offered.
+const KEYWORDS: [(&str, SuggestionKind, &str); 20] = [
+    ("p1", SuggestionKind::Priority, "High priority"),
+    ("p2", SuggestionKind::Priority, "Medium priority"),
+    ("p3", SuggestionKind::Priority, "Low priority"),
+    ("today", SuggestionKind::DueDate, "Due today"),
+    ("tomorrow", SuggestionKind::DueDate, "Due tomorrow"),
+    ("mon", SuggestionKind::DueDate, "Due next Monday"),
+    ("tue", SuggestionKind::DueDate, "Due next Tuesday"),
+    ("wed", SuggestionKind::DueDate, "Due next Wednesday"),
+    ("thu", SuggestionKind::DueDate, "Due next Thursday"),
+    ("fri", SuggestionKind::DueDate, "Due next Friday"),
+    ("sat", SuggestionKind::DueDate, "Due next Saturday"),
+    ("sun", SuggestionKind::DueDate, "Due next Sunday"),
+    ("next-week", SuggestionKind::DueDate, "Due next Monday"),
+    (
+        "next-month",
+        SuggestionKind::DueDate,
+        "Due on the 1st of next month",
+    ),
+    ("daily", SuggestionKind::Repeat, "Repeats every day"),
+    (
+        "every-weekday",
+        SuggestionKind::Repeat,
+        "Repeats Monday to Friday",
+    ),
+    ("weekly", SuggestionKind::Repeat, "Repeats every week"),
+    (
+        "every-2-weeks",
+        SuggestionKind::Repeat,
+        "Repeats every other week",
+    ),
+    ("monthly", SuggestionKind::Repeat, "Repeats every month"),
+    ("yearly", SuggestionKind::Repeat, "Repeats every year"),
+];
+
+/// Suggestions for the `#` or `@` tag the cursor, a byte offset into
+/// `line`, is at the end of or inside: the board's `tags`, then
+/// priorities, due dates and repeat rules starting with what has been
+/// typed. `@` only takes due dates. `None` when the cursor is not in a tag
+/// or nothing fits.
+pub fn complete_tag(line: &str, cursor: usize, tags: &[String]) -> Option<Completions> {
+    // Tags are only read before the first `|`
+    let head = split_unescaped(line, '|').into_iter().next()?;
+    if cursor > head.end || !line.is_char_boundary(cursor) {
+        return None;
+    }
+    let start = line[..cursor].rfind(char::is_whitespace).map_or(0, |i| {
+        i + line[i..].chars().next().map_or(1, char::len_utf8)
+    });
+    // Before the `#` of a tag is not in it
+    if cursor <= start {
+        return None;
+    }
+    let end = line[cursor..head.end]
+        .find(char::is_whitespace)
+        .map_or(head.end, |i| cursor + i);
+    let word = &line[start..end];
+    let sigil = word.chars().next().filter(|&c| c == '#' || c == '@')?;
+    let typed = line[start + 1..cursor].to_lowercase();
+
+    let tags = tags
+        .iter()
+        .filter(|_| sigil == '#')
+        .map(|tag| (tag.as_str(), SuggestionKind::Tag, "Tag"));
+    let keywords = KEYWORDS
+        .iter()
+        .copied()
+        .filter(|&(_, kind, _)| sigil == '#' || kind == SuggestionKind::DueDate);
+    let suggestions: Vec<Suggestion> = tags
+        .chain(keywords)
+        .filter(|(text, _, _)| text.starts_with(&typed) && !word[1..].eq_ignore_ascii_case(text))
+        .map(|(text, kind, hint)| Suggestion {
+            text: format!("{}{}", sigil, text),
+            kind,
+            hint: hint.to_string(),
+        })
+        .collect();
+    if suggestions.is_empty() {
+        return None;
+    }
+    Some(Completions {
+        span: start..end,
+        suggestions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggest(line: &str, cursor: usize) -> Vec<String> {
+        let tags = ["travel".to_string(), "work".to_string()];
+        complete_tag(line, cursor, &tags)
+            .map(|completions| {
+                completions
+                    .suggestions
+                    .into_iter()
+                    .map(|suggestion| suggestion.text)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn tags_come_before_keywords() {
+        assert_eq!(
+            suggest("Plan #t", 7),
+            ["#travel", "#today", "#tomorrow", "#tue", "#thu"]
+        );
+        assert_eq!(suggest("Plan #P", 7), ["#p1", "#p2", "#p3"]);
+        assert_eq!(suggest("Plan #work", 10), Vec::<String>::new());
+        assert_eq!(suggest("Plan", 4), Vec::<String>::new());
+        // Just before a tag
+        assert_eq!(suggest("Plan #t", 5), Vec::<String>::new());
+        assert_eq!(suggest("#t", 0), Vec::<String>::new());
+        assert_eq!(
+            suggest("Plan @t", 7),
+            ["@today", "@tomorrow", "@tue", "@thu"]
+        );
+    }
+
+    #[test]
+    fn the_whole_tag_is_replaced() {
+        let completions = complete_tag("Plan #wo trip", 7, &["work".to_string()]).unwrap();
+        assert_eq!(completions.span, 5..8);
+        // Only what is before the cursor counts
+        assert_eq!(
+            complete_tag("Plan #wx trip", 7, &["work".to_string()])
+                .unwrap()
+                .span,
+            5..8
+        );
+        // Not in checklist items
+        assert_eq!(complete_tag("Plan | #wo", 10, &["work".to_string()]), None);
+    }
+}
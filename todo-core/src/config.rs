@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveTime;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::dates::DEFAULT_DUE_TIME;
+use crate::error::StorageError;
+use crate::filter::SavedFilter;
+use crate::paths;
+use crate::reminders::{DEFAULT_REMINDER_MINUTES, DEFAULT_SNOOZE_MINUTES};
+use crate::storage::DEFAULT_BACKUP_COUNT;
+
+/// Name of the settings file inside [`paths::config_dir`].
+pub const CONFIG_FILE_NAME: &str = "config.json";
+/// Name of the optional user stylesheet inside [`paths::config_dir`].
+pub const STYLE_FILE_NAME: &str = "style.css";
+
+/// User settings shared by the GUI and the CLI. Every field has a default, so
+/// the file only needs the settings that differ from it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Number of timestamped backups kept next to the task file.
+    pub backup_count: usize,
+    /// Time of day for due dates given without one, such as `#tomorrow`.
+    /// Written as `"HH:MM"`.
+    #[serde(with = "hours_minutes")]
+    pub default_due_time: NaiveTime,
+    /// Minutes before a task's due time to remind at; `0` is at the due
+    /// time and an empty list turns reminders off.
+    pub reminders: Vec<u32>,
+    /// How long a snoozed reminder waits before it comes back.
+    pub snooze_minutes: u32,
+    /// Keyboard shortcuts replacing the GUI's defaults, by action name,
+    /// such as `"app.delete-task": ["Delete", "<Control>d"]`. An empty list
+    /// leaves the action without a shortcut.
+    pub shortcuts: BTreeMap<String, Vec<String>>,
+    /// Filters the GUI's command palette can apply, by name.
+    pub filters: BTreeMap<String, SavedFilter>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            backup_count: DEFAULT_BACKUP_COUNT,
+            default_due_time: DEFAULT_DUE_TIME,
+            reminders: DEFAULT_REMINDER_MINUTES.to_vec(),
+            snooze_minutes: DEFAULT_SNOOZE_MINUTES,
+            shortcuts: BTreeMap::new(),
+            filters: BTreeMap::new(),
+        }
+    }
+}
+
+/// (De)serializes a time of day as `"HH:MM"`.
+mod hours_minutes {
+    use super::*;
+
+    const FORMAT: &str = "%H:%M";
+
+    pub fn serialize<S: Serializer>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&time.format(FORMAT).to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        NaiveTime::parse_from_str(&text, FORMAT).map_err(|_| {
+            serde::de::Error::custom(format!("invalid time '{}' (expected HH:MM)", text))
+        })
+    }
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/todo-gui/config.json`.
+    pub fn path() -> Option<PathBuf> {
+        paths::config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
+    }
+
+    /// Loads the user's settings, or the defaults if there is no config file.
+    pub fn load() -> Result<Config, StorageError> {
+        match Config::path() {
+            Some(path) => Config::load_from(&path),
+            None => Ok(Config::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Config, StorageError> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let file = fs::File::open(path).map_err(StorageError::io(path))?;
+        serde_json::from_reader(BufReader::new(file)).map_err(StorageError::parse(path))
+    }
+}
@@ -0,0 +1,221 @@
+//! Due dates written in the quick-add line.
+//!
+//! A due date tag starts with `#` or `@` and is one of:
+//!
+//! * a date: `2026-10-20`, `today`, `tomorrow` (`tmr`), a weekday such as
+//!   `fri` or `friday`, `next-week` (next Monday) or `next-month` (the first
+//!   of next month);
+//! * a relative offset: `in3d` and `in2w` for a date, or `in4h` for a time
+//!   of day four hours from now;
+//! * a time of day, on its own for today or after a date and `_`, in 24-hour
+//!   (`17:30`) or 12-hour (`5pm`, `5:30pm`) form.
+//!
+//! Dates without a time are due at the configured default time. A weekday
+//! always means the next one after today, so `#fri` on a Friday is a week
+//! away.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Time of day for due dates given without one, unless configured otherwise.
+pub const DEFAULT_DUE_TIME: NaiveTime = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+
+/// Resolves the text of a due date tag, without its `#` or `@`, against
+/// `now`. `None` if it is not a due date.
+pub(crate) fn parse_due(
+    tag: &str,
+    now: NaiveDateTime,
+    default_time: NaiveTime,
+) -> Option<NaiveDateTime> {
+    let tag = tag.to_lowercase();
+    if let Some(due) = parse_offset(&tag, now, default_time) {
+        return Some(due);
+    }
+    if let Some(time) = parse_time(&tag) {
+        return Some(now.date().and_time(time));
+    }
+
+    let (date, time) = match tag.split_once('_') {
+        Some((date, time)) => (date, Some(parse_time(time)?)),
+        None => (tag.as_str(), None),
+    };
+    let date = parse_date(date, now.date())?;
+    Some(date.and_time(time.unwrap_or(default_time)))
+}
+
+/// `in3d`, `in2w` or `in4h`. Only hours keep the time of day; days and
+/// weeks give a date, due at `default_time` like `tomorrow`.
+fn parse_offset(tag: &str, now: NaiveDateTime, default_time: NaiveTime) -> Option<NaiveDateTime> {
+    lazy_static! {
+        static ref OFFSET_RE: Regex = Regex::new(r"^in(\d{1,4})([dwh])$").unwrap();
+    }
+    let captures = OFFSET_RE.captures(tag)?;
+    let count: i64 = captures[1].parse().ok()?;
+    let days = match &captures[2] {
+        "h" => return now.checked_add_signed(Duration::hours(count)),
+        "d" => Duration::days(count),
+        _ => Duration::weeks(count),
+    };
+    Some(now.date().checked_add_signed(days)?.and_time(default_time))
+}
+
+fn parse_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Some(date);
+    }
+    match text {
+        "today" => Some(today),
+        "tomorrow" | "tmr" | "tmrw" => today.succ_opt(),
+        "next-week" => {
+            let days_to_monday = 7 - i64::from(today.weekday().num_days_from_monday());
+            today.checked_add_signed(Duration::days(days_to_monday))
+        }
+        "next-month" => {
+            let (year, month) = match today.month() {
+                12 => (today.year() + 1, 1),
+                month => (today.year(), month + 1),
+            };
+            NaiveDate::from_ymd_opt(year, month, 1)
+        }
+        _ => {
+            let weekday = parse_weekday(text)?;
+            let days_ahead =
+                (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+            let days_ahead = if days_ahead == 0 { 7 } else { days_ahead };
+            today.checked_add_signed(Duration::days(i64::from(days_ahead)))
+        }
+    }
+}
+
+fn parse_weekday(text: &str) -> Option<Weekday> {
+    match text {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// `17:30`, `9:05`, `5pm` or `5:30am`. A bare number is not a time.
+fn parse_time(text: &str) -> Option<NaiveTime> {
+    lazy_static! {
+        static ref TIME_RE: Regex = Regex::new(r"^(\d{1,2})(?::(\d{2}))?(am|pm)?$").unwrap();
+    }
+    let captures = TIME_RE.captures(text)?;
+    let hour: u32 = captures[1].parse().ok()?;
+    let minute: u32 = captures
+        .get(2)
+        .map_or(Some(0), |m| m.as_str().parse().ok())?;
+    let hour = match captures.get(3).map(|m| m.as_str()) {
+        None if captures.get(2).is_none() => return None,
+        None => hour,
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some("am") => hour % 12,
+        Some(_) => hour % 12 + 12,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::now;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> Option<NaiveDateTime> {
+        NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, 0)
+    }
+
+    fn due(tag: &str) -> Option<NaiveDateTime> {
+        parse_due(tag, now(), DEFAULT_DUE_TIME)
+    }
+
+    #[test]
+    fn absolute_dates() {
+        assert_eq!(due("2026-10-20"), at(2026, 10, 20, 9, 0));
+        assert_eq!(due("2026-10-20_17:00"), at(2026, 10, 20, 17, 0));
+        assert_eq!(due("2025-07-05_10:00"), at(2025, 7, 5, 10, 0));
+        assert_eq!(due("2026-13-01"), None);
+        assert_eq!(due("2026-10-20_25:00"), None);
+    }
+
+    #[test]
+    fn today_and_tomorrow() {
+        assert_eq!(due("today"), at(2026, 10, 14, 9, 0));
+        assert_eq!(due("tomorrow"), at(2026, 10, 15, 9, 0));
+        assert_eq!(due("tmr"), at(2026, 10, 15, 9, 0));
+        assert_eq!(due("Tomorrow_17:45"), at(2026, 10, 15, 17, 45));
+    }
+
+    #[test]
+    fn weekdays_are_the_next_one_after_today() {
+        assert_eq!(due("thu"), at(2026, 10, 15, 9, 0));
+        assert_eq!(due("fri"), at(2026, 10, 16, 9, 0));
+        assert_eq!(due("friday"), at(2026, 10, 16, 9, 0));
+        assert_eq!(due("mon"), at(2026, 10, 19, 9, 0));
+        assert_eq!(due("tue"), at(2026, 10, 20, 9, 0));
+        // Today is a Wednesday
+        assert_eq!(due("wed"), at(2026, 10, 21, 9, 0));
+        assert_eq!(due("fri_5pm"), at(2026, 10, 16, 17, 0));
+    }
+
+    #[test]
+    fn next_week_and_month() {
+        assert_eq!(due("next-week"), at(2026, 10, 19, 9, 0));
+        assert_eq!(due("next-month"), at(2026, 11, 1, 9, 0));
+        let december = at(2026, 12, 31, 8, 0).unwrap();
+        assert_eq!(
+            parse_due("next-month", december, DEFAULT_DUE_TIME),
+            at(2027, 1, 1, 9, 0)
+        );
+    }
+
+    #[test]
+    fn relative_offsets() {
+        assert_eq!(due("in3d"), at(2026, 10, 17, 9, 0));
+        assert_eq!(due("in2w"), at(2026, 10, 28, 9, 0));
+        assert_eq!(due("in4h"), at(2026, 10, 14, 19, 30));
+        let five_pm = NaiveTime::from_hms_opt(17, 0, 0).unwrap();
+        assert_eq!(parse_due("in3d", now(), five_pm), at(2026, 10, 17, 17, 0));
+        assert_eq!(parse_due("in4h", now(), five_pm), at(2026, 10, 14, 19, 30));
+        assert_eq!(due("in3x"), None);
+    }
+
+    #[test]
+    fn twelve_and_twenty_four_hour_times() {
+        assert_eq!(due("17:30"), at(2026, 10, 14, 17, 30));
+        assert_eq!(due("9:05"), at(2026, 10, 14, 9, 5));
+        assert_eq!(due("5pm"), at(2026, 10, 14, 17, 0));
+        assert_eq!(due("5:30am"), at(2026, 10, 14, 5, 30));
+        assert_eq!(due("12am"), at(2026, 10, 14, 0, 0));
+        assert_eq!(due("12pm"), at(2026, 10, 14, 12, 0));
+        assert_eq!(due("13pm"), None);
+        // A bare number is a tag, not a time
+        assert_eq!(due("5"), None);
+    }
+
+    #[test]
+    fn default_time_is_configurable() {
+        let evening = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        assert_eq!(
+            parse_due("tomorrow", now(), evening),
+            at(2026, 10, 15, 18, 0)
+        );
+        assert_eq!(
+            parse_due("tomorrow_8am", now(), evening),
+            at(2026, 10, 15, 8, 0)
+        );
+    }
+
+    #[test]
+    fn other_words_are_not_dates() {
+        assert_eq!(due("home"), None);
+        assert_eq!(due("work_stuff"), None);
+        assert_eq!(due("2025"), None);
+    }
+}
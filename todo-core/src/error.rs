@@ -0,0 +1,94 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Why reading or writing a task file failed.
+#[derive(Debug)]
+pub enum StorageError {
+    /// The file (or a backup next to it) could not be read or written.
+    Io { path: PathBuf, source: io::Error },
+    /// The file was read but its contents could not be parsed.
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    /// The file is valid JSON but not in any known task file layout.
+    Malformed { path: PathBuf, reason: String },
+    /// The file was written by a newer version of the app.
+    UnsupportedVersion { path: PathBuf, version: u32 },
+    /// The file could not be loaded, so saving the board shown in its place
+    /// would overwrite tasks that were never read.
+    NotLoaded { path: PathBuf },
+}
+
+impl StorageError {
+    pub(crate) fn io(path: &Path) -> impl FnOnce(io::Error) -> StorageError + '_ {
+        move |source| StorageError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub(crate) fn parse(path: &Path) -> impl FnOnce(serde_json::Error) -> StorageError + '_ {
+        move |source| StorageError::Parse {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub(crate) fn malformed(path: &Path) -> impl FnOnce(String) -> StorageError + '_ {
+        move |reason| StorageError::Malformed {
+            path: path.to_path_buf(),
+            reason,
+        }
+    }
+
+    /// The file the failed operation was working on.
+    pub fn path(&self) -> &Path {
+        match self {
+            StorageError::Io { path, .. }
+            | StorageError::Parse { path, .. }
+            | StorageError::Malformed { path, .. }
+            | StorageError::UnsupportedVersion { path, .. }
+            | StorageError::NotLoaded { path } => path,
+        }
+    }
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            StorageError::Parse { path, source } => {
+                write!(f, "could not parse {}: {}", path.display(), source)
+            }
+            StorageError::Malformed { path, reason } => {
+                write!(f, "{} is not a valid task file: {}", path.display(), reason)
+            }
+            StorageError::UnsupportedVersion { path, version } => write!(
+                f,
+                "{} uses schema version {}, but this version of the app only understands up to {}",
+                path.display(),
+                version,
+                crate::schema::CURRENT_SCHEMA_VERSION
+            ),
+            StorageError::NotLoaded { path } => write!(
+                f,
+                "{} could not be loaded, so it is not saved over; fix or move it and retry",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StorageError::Io { source, .. } => Some(source),
+            StorageError::Parse { source, .. } => Some(source),
+            StorageError::Malformed { .. }
+            | StorageError::UnsupportedVersion { .. }
+            | StorageError::NotLoaded { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::fmt;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::model::Task;
+use crate::search::SearchQuery;
+use crate::workflow::Workflow;
+
+/// How the tags of a [`TaskFilter`] combine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TagMatch {
+    /// Tasks must have every tag.
+    #[default]
+    All,
+    /// Tasks must have at least one of the tags.
+    Any,
+}
+
+/// Which due dates a [`TaskFilter`] lets through. The smart filters,
+/// [`Overdue`](Self::Overdue), [`Today`](Self::Today) and
+/// [`Next7Days`](Self::Next7Days), leave out tasks in completed columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueFilter {
+    /// Due on this day.
+    On(NaiveDate),
+    /// Without a due date.
+    NoDate,
+    /// Due before now.
+    Overdue,
+    /// Due at any time today, or overdue from an earlier day.
+    Today,
+    /// Overdue, or due within the next seven days.
+    Next7Days,
+}
+
+impl fmt::Display for DueFilter {
+    /// The text [`parse_due_filter`] reads back as this filter.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DueFilter::On(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            DueFilter::NoDate => f.write_str("none"),
+            DueFilter::Overdue => f.write_str("overdue"),
+            DueFilter::Today => f.write_str("today"),
+            DueFilter::Next7Days => f.write_str("week"),
+        }
+    }
+}
+
+/// How soon a task is due, for highlighting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Overdue,
+    /// Due later today.
+    Today,
+    /// Due within the next seven days, after today.
+    ThisWeek,
+}
+
+impl Urgency {
+    /// The urgency of a task due at `due_time`, or `None` if it is due more
+    /// than a week from `now`.
+    pub fn of(due_time: NaiveDateTime, now: NaiveDateTime) -> Option<Urgency> {
+        if due_time < now {
+            Some(Urgency::Overdue)
+        } else if due_time.date() == now.date() {
+            Some(Urgency::Today)
+        } else if due_time.date() <= now.date() + Duration::days(7) {
+            Some(Urgency::ThisWeek)
+        } else {
+            None
+        }
+    }
+}
+
+/// The search, tag and due-date filters applied to the board.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskFilter {
+    /// Only show tasks with these (lowercase) tags, combined as `tag_match`
+    /// says. Empty shows tasks regardless of their tags.
+    pub tags: Vec<String>,
+    pub tag_match: TagMatch,
+    /// `None` disables the due date filter.
+    pub due_date: Option<DueFilter>,
+    pub search: SearchQuery,
+}
+
+/// A filter kept under a name in the config, for the GUI's command palette
+/// to apply in one go. Every field is optional:
+/// `{ "tags": ["work"], "due": "week" }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedFilter {
+    /// Search box text, such as `report -draft`.
+    pub search: String,
+    pub tags: Vec<String>,
+    /// Show tasks with any of the tags instead of all of them.
+    pub match_any: bool,
+    /// Due date filter text, as read by [`parse_due_filter`].
+    pub due: Option<String>,
+}
+
+impl SavedFilter {
+    /// The filter to apply, or why its due date filter cannot be read.
+    pub fn to_filter(&self) -> Result<TaskFilter, String> {
+        Ok(TaskFilter {
+            tags: self.tags.iter().map(|tag| tag.to_lowercase()).collect(),
+            tag_match: if self.match_any {
+                TagMatch::Any
+            } else {
+                TagMatch::All
+            },
+            due_date: self.due.as_deref().map(parse_due_filter).transpose()?,
+            search: SearchQuery::parse(&self.search),
+        })
+    }
+}
+
+/// Parses the text of a due date filter: `YYYY-MM-DD` for tasks due that
+/// day, `none` for tasks without a due date, or one of the smart filters
+/// `overdue`, `today` and `week` (the next seven days).
+pub fn parse_due_filter(text: &str) -> Result<DueFilter, String> {
+    let text = text.trim();
+    match text.to_lowercase().as_str() {
+        "none" => Ok(DueFilter::NoDate),
+        "overdue" => Ok(DueFilter::Overdue),
+        "today" => Ok(DueFilter::Today),
+        "week" | "7d" | "next-7-days" => Ok(DueFilter::Next7Days),
+        _ => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .map(DueFilter::On)
+            .map_err(|_| {
+                format!(
+                    "invalid due date '{}' (expected YYYY-MM-DD, none, overdue, today or week)",
+                    text
+                )
+            }),
+    }
+}
+
+impl TaskFilter {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.due_date.is_none() && self.search.is_empty()
+    }
+
+    /// Whether `task` passes the filter at time `now`.
+    pub fn matches(&self, task: &Task, workflow: &Workflow, now: NaiveDateTime) -> bool {
+        if !self.search.matches(task) {
+            return false;
+        }
+
+        // Tag filter
+        if !self.tags.is_empty() {
+            let has_tag = |tag: &String| task.tags.contains(tag);
+            let tags_match = match self.tag_match {
+                TagMatch::All => self.tags.iter().all(has_tag),
+                TagMatch::Any => self.tags.iter().any(has_tag),
+            };
+            if !tags_match {
+                return false;
+            }
+        }
+
+        // Due date filter
+        let open = || !workflow.is_completed(&task.status);
+        let due_by = |end: NaiveDate| task.due_time.is_some_and(|due| due.date() <= end);
+        match self.due_date {
+            Some(DueFilter::On(date)) => task.due_time.is_some_and(|due| due.date() == date),
+            Some(DueFilter::NoDate) => task.due_time.is_none(),
+            Some(DueFilter::Overdue) => open() && task.due_time.is_some_and(|due| due < now),
+            Some(DueFilter::Today) => open() && due_by(now.date()),
+            Some(DueFilter::Next7Days) => open() && due_by(now.date() + Duration::days(7)),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::{self, now};
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn task(status: &str, due_time: Option<NaiveDateTime>) -> Task {
+        Task {
+            status: status.to_string(),
+            due_time,
+            ..testing::task("")
+        }
+    }
+
+    /// Which of `tasks` pass a filter for `due`.
+    fn passing(due: &str, tasks: &[Task]) -> Vec<usize> {
+        let filter = TaskFilter {
+            due_date: Some(parse_due_filter(due).unwrap()),
+            ..Default::default()
+        };
+        (0..tasks.len())
+            .filter(|&i| filter.matches(&tasks[i], &Workflow::default(), now()))
+            .collect()
+    }
+
+    #[test]
+    fn smart_due_filters() {
+        let tasks = [
+            task("todo", Some(at(13, 9, 0))),
+            task("todo", Some(at(14, 9, 0))),
+            task("todo", Some(at(14, 17, 0))),
+            task("doing", Some(at(21, 9, 0))),
+            task("todo", Some(at(22, 9, 0))),
+            task("todo", None),
+            task("done", Some(at(13, 9, 0))),
+        ];
+        assert_eq!(passing("overdue", &tasks), [0, 1]);
+        assert_eq!(passing("today", &tasks), [0, 1, 2]);
+        assert_eq!(passing("week", &tasks), [0, 1, 2, 3]);
+        assert_eq!(passing("none", &tasks), [5]);
+        // An exact date includes completed tasks
+        assert_eq!(passing("2026-10-13", &tasks), [0, 6]);
+    }
+
+    #[test]
+    fn due_filters_round_trip() {
+        for text in ["2026-10-20", "none", "overdue", "today", "week"] {
+            assert_eq!(parse_due_filter(text).unwrap().to_string(), text);
+        }
+        assert!(parse_due_filter("tomorrow").is_err());
+    }
+
+    #[test]
+    fn saved_filters() {
+        let saved: SavedFilter =
+            serde_json::from_str(r#"{ "tags": ["Work"], "due": "week", "match_any": true }"#)
+                .unwrap();
+        let filter = saved.to_filter().unwrap();
+        assert_eq!(filter.tags, ["work"]);
+        assert_eq!(filter.tag_match, TagMatch::Any);
+        assert_eq!(filter.due_date, Some(DueFilter::Next7Days));
+        assert!(filter.search.is_empty());
+
+        let saved = SavedFilter {
+            due: Some("soon".to_string()),
+            ..SavedFilter::default()
+        };
+        assert!(saved.to_filter().is_err());
+    }
+
+    #[test]
+    fn urgency() {
+        assert_eq!(Urgency::of(at(14, 15, 0), now()), Some(Urgency::Overdue));
+        assert_eq!(Urgency::of(at(14, 23, 0), now()), Some(Urgency::Today));
+        assert_eq!(Urgency::of(at(21, 23, 0), now()), Some(Urgency::ThisWeek));
+        assert_eq!(Urgency::of(at(22, 0, 0), now()), None);
+    }
+}
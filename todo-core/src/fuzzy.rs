@@ -0,0 +1,154 @@
+//! Fuzzy matching for the command palette.
+//!
+//! A query matches a text when its letters appear in the text in order,
+//! ignoring case and spaces, so `mvdone` matches "Move to Done". Matches
+//! at the start of words and runs of consecutive letters score higher.
+
+use std::ops::Range;
+
+/// Score of each matched letter.
+const MATCH: i32 = 16;
+/// Extra score for a letter starting a word.
+const WORD_START: i32 = 10;
+/// Extra score for a letter right after the previously matched one.
+const CONSECUTIVE: i32 = 8;
+/// Penalty for each letter skipped between two matched ones.
+const GAP: i32 = 1;
+
+/// How well a query matches a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better; only comparable between matches of the same query.
+    pub score: i32,
+    /// Byte ranges of the text's matched letters, in order and merged where
+    /// they touch, for highlighting.
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// Matches `query` against `text`, finding the placement of its letters
+/// that scores highest. An empty query matches everything with score 0.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            ranges: Vec::new(),
+        });
+    }
+    if query.len() > chars.len() {
+        return None;
+    }
+
+    let word_start = |j: usize| {
+        let c = chars[j].1;
+        match j.checked_sub(1).map(|i| chars[i].1) {
+            None => true,
+            Some(before) => {
+                !before.is_alphanumeric() || (before.is_lowercase() && c.is_uppercase())
+            }
+        }
+    };
+    let letter_score = |j: usize| MATCH + if word_start(j) { WORD_START } else { 0 };
+    let same = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+
+    // scores[i][j]: best score with the query's letter i matched at text
+    // letter j; from[i][j]: where letter i - 1 was matched for that score
+    let mut scores = vec![vec![None; chars.len()]; query.len()];
+    let mut from = vec![vec![0; chars.len()]; query.len()];
+    for (j, &(_, c)) in chars.iter().enumerate() {
+        if same(query[0], c) {
+            scores[0][j] = Some(letter_score(j));
+        }
+    }
+    for i in 1..query.len() {
+        // Best of scores[i - 1][k] + GAP * k over k < j - 1, so skipping
+        // from k to j costs GAP * (j - 1 - k)
+        let mut gapped: Option<(i32, usize)> = None;
+        for j in i..chars.len() {
+            let before = scores[i - 1][j - 1];
+            if same(query[i], chars[j].1) {
+                let after_gap = gapped.map(|(best, k)| (best - GAP * (j as i32 - 1), k));
+                let consecutive = before.map(|score| (score + CONSECUTIVE, j - 1));
+                let best = match (after_gap, consecutive) {
+                    (Some(a), Some(c)) if a.0 > c.0 => Some(a),
+                    (a, None) => a,
+                    (_, c) => c,
+                };
+                if let Some((score, k)) = best {
+                    scores[i][j] = Some(score + letter_score(j));
+                    from[i][j] = k;
+                }
+            }
+            if let Some(score) = before {
+                let score = score + GAP * (j as i32 - 1);
+                if gapped.is_none_or(|(best, _)| score > best) {
+                    gapped = Some((score, j - 1));
+                }
+            }
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut j, score) = scores[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| Some((j, (*score)?)))
+        .max_by_key(|&(j, score)| (score, std::cmp::Reverse(j)))?;
+    let mut matched = vec![j; query.len()];
+    for i in (1..query.len()).rev() {
+        j = from[i][j];
+        matched[i - 1] = j;
+    }
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for j in matched {
+        let (start, c) = chars[j];
+        match ranges.last_mut() {
+            Some(last) if last.end == start => last.end += c.len_utf8(),
+            _ => ranges.push(start..start + c.len_utf8()),
+        }
+    }
+    Some(FuzzyMatch { score, ranges })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The matched ranges as `(start, end)` pairs.
+    fn ranges(query: &str, text: &str) -> Option<Vec<(usize, usize)>> {
+        let m = fuzzy_match(query, text)?;
+        Some(m.ranges.iter().map(|r| (r.start, r.end)).collect())
+    }
+
+    fn score(query: &str, text: &str) -> i32 {
+        fuzzy_match(query, text).unwrap().score
+    }
+
+    #[test]
+    fn letters_in_order_ignoring_case_and_spaces() {
+        assert_eq!(
+            ranges("mvd", "Move to Done"),
+            Some(vec![(0, 1), (2, 3), (8, 9)])
+        );
+        assert_eq!(ranges("to do", "Move to Done"), Some(vec![(5, 7), (8, 10)]));
+        assert_eq!(ranges("dm", "Move to Done"), None);
+        assert_eq!(ranges("", "Move"), Some(vec![]));
+        assert_eq!(ranges("moves", "Move"), None);
+        // Offsets are bytes, also after wider characters
+        assert_eq!(ranges("ün", "Grün"), Some(vec![(2, 5)]));
+    }
+
+    #[test]
+    fn prefers_word_starts_and_runs() {
+        // The d of Done rather than the one in "Add"
+        assert_eq!(ranges("d", "Add to Done"), Some(vec![(7, 8)]));
+        assert_eq!(ranges("td", "Add to Done"), Some(vec![(4, 5), (7, 8)]));
+        assert_eq!(ranges("do", "Add to Done"), Some(vec![(7, 9)]));
+
+        assert!(score("ed", "Edit task") > score("ed", "Rename board"));
+        assert!(score("del", "Delete task") > score("del", "Move to Doing, Eleven"));
+        assert!(score("nt", "New task") > score("nt", "Next board"));
+    }
+}
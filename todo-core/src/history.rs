@@ -0,0 +1,152 @@
+//! Undo and redo for [`AppState`](crate::AppState).
+//!
+//! Every mutation is recorded as a [`Change`]: the list of primitive
+//! [`Edit`]s it made to the task list, in order. Undoing applies the inverse
+//! of each edit in reverse order, redoing applies them again, so both restore
+//! the exact task order without keeping copies of the whole list.
+
+use crate::model::Task;
+use crate::workflow::Workflow;
+
+/// How many changes can be undone.
+const HISTORY_LIMIT: usize = 100;
+
+#[derive(Debug, Clone)]
+pub(crate) enum Edit {
+    Insert {
+        index: usize,
+        task: Task,
+    },
+    Remove {
+        index: usize,
+        task: Task,
+    },
+    /// Boxed so an edit is not the size of two tasks.
+    Replace {
+        index: usize,
+        before: Box<Task>,
+        after: Box<Task>,
+    },
+    SetWorkflow {
+        before: Workflow,
+        after: Workflow,
+    },
+}
+
+impl Edit {
+    pub(crate) fn apply(&self, tasks: &mut Vec<Task>, workflow: &mut Workflow) {
+        match self {
+            Edit::Insert { index, task } => tasks.insert(*index, task.clone()),
+            Edit::Remove { index, .. } => {
+                tasks.remove(*index);
+            }
+            Edit::Replace { index, after, .. } => tasks[*index] = (**after).clone(),
+            Edit::SetWorkflow { after, .. } => *workflow = after.clone(),
+        }
+    }
+
+    fn inverse(&self) -> Edit {
+        match self.clone() {
+            Edit::Insert { index, task } => Edit::Remove { index, task },
+            Edit::Remove { index, task } => Edit::Insert { index, task },
+            Edit::Replace {
+                index,
+                before,
+                after,
+            } => Edit::Replace {
+                index,
+                before: after,
+                after: before,
+            },
+            Edit::SetWorkflow { before, after } => Edit::SetWorkflow {
+                before: after,
+                after: before,
+            },
+        }
+    }
+}
+
+/// One undoable user action, such as deleting a task.
+#[derive(Debug, Clone)]
+pub(crate) struct Change {
+    /// Short description, e.g. "Delete task".
+    pub(crate) label: String,
+    edits: Vec<Edit>,
+}
+
+impl Change {
+    fn revert(&self, tasks: &mut Vec<Task>, workflow: &mut Workflow) {
+        for edit in self.edits.iter().rev() {
+            edit.inverse().apply(tasks, workflow);
+        }
+    }
+
+    fn reapply(&self, tasks: &mut Vec<Task>, workflow: &mut Workflow) {
+        for edit in &self.edits {
+            edit.apply(tasks, workflow);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct History {
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+}
+
+impl History {
+    /// Records a new change. Anything that was undone can no longer be
+    /// redone after this.
+    pub(crate) fn record(&mut self, label: &str, edits: Vec<Edit>) {
+        if edits.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        self.undo.push(Change {
+            label: label.to_string(),
+            edits,
+        });
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Reverts the latest change and returns its label.
+    pub(crate) fn undo(
+        &mut self,
+        tasks: &mut Vec<Task>,
+        workflow: &mut Workflow,
+    ) -> Option<String> {
+        let change = self.undo.pop()?;
+        change.revert(tasks, workflow);
+        let label = change.label.clone();
+        self.redo.push(change);
+        Some(label)
+    }
+
+    /// Applies the latest undone change again and returns its label.
+    pub(crate) fn redo(
+        &mut self,
+        tasks: &mut Vec<Task>,
+        workflow: &mut Workflow,
+    ) -> Option<String> {
+        let change = self.redo.pop()?;
+        change.reapply(tasks, workflow);
+        let label = change.label.clone();
+        self.undo.push(change);
+        Some(label)
+    }
+
+    pub(crate) fn undo_label(&self) -> Option<&str> {
+        self.undo.last().map(|c| c.label.as_str())
+    }
+
+    pub(crate) fn redo_label(&self) -> Option<&str> {
+        self.redo.last().map(|c| c.label.as_str())
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
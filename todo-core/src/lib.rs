@@ -0,0 +1,52 @@
+//! Headless task model for the To-Do app.
+//!
+//! Everything that does not need a display lives here: the task data
+//! structures, the quick-add parser, persistence and filtering. The GTK
+//! front-end in the `guirs` binary is a thin layer on top of [`AppState`].
+
+mod boards;
+mod completion;
+mod config;
+mod dates;
+mod error;
+mod filter;
+mod fuzzy;
+mod history;
+mod model;
+mod parser;
+pub mod paths;
+mod recurrence;
+mod reminders;
+mod schema;
+mod search;
+mod sort;
+mod state;
+mod storage;
+#[cfg(test)]
+mod testing;
+mod workflow;
+
+pub use boards::{BOARDS_FILE_NAME, Board, BoardError, BoardRegistry, DEFAULT_BOARD_NAME};
+pub use completion::{Completions, Suggestion, SuggestionKind, complete_tag};
+pub use config::{CONFIG_FILE_NAME, Config, STYLE_FILE_NAME};
+pub use dates::DEFAULT_DUE_TIME;
+pub use error::StorageError;
+pub use filter::{DueFilter, SavedFilter, TagMatch, TaskFilter, Urgency, parse_due_filter};
+pub use fuzzy::{FuzzyMatch, fuzzy_match};
+pub use model::{ChecklistItem, Priority, Task};
+pub use parser::{
+    ParsedTask, SyntaxError, SyntaxErrorKind, Token, TokenKind, check_task_description,
+    format_task_description, parse_task_description, parse_task_description_at,
+    tokenize_task_description,
+};
+pub use recurrence::{Frequency, Recurrence};
+pub use reminders::{DEFAULT_REMINDER_MINUTES, DEFAULT_SNOOZE_MINUTES, Reminder, format_minutes};
+pub use schema::CURRENT_SCHEMA_VERSION;
+pub use search::SearchQuery;
+pub use sort::SortMode;
+pub use state::AppState;
+pub use storage::{
+    Backup, BoardData, DEFAULT_BACKUP_COUNT, list_backups, load_tasks, read_tasks, save_tasks,
+    set_aside_unreadable,
+};
+pub use workflow::{Column, MoveError, Workflow, WorkflowError};
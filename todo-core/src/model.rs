@@ -0,0 +1,81 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::recurrence::Recurrence;
+
+// --- Data Structures ---
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub enum Priority {
+    Low,
+    #[default]
+    Medium, // Default priority for new tasks
+    High,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    pub id: Uuid,
+    pub description: String,
+    /// Id of the [`Column`](crate::Column) the task is in.
+    pub status: String,
+    /// Lowercase tags without the `#`, in the order they were written.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub due_time: Option<NaiveDateTime>,
+    pub priority: Priority,
+    /// Manual sort order within the task's column, lowest first. Files from
+    /// before manual ordering keep the order the tasks were stored in.
+    #[serde(default)]
+    pub position: u32,
+    /// Steps of the task, in order.
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    /// Free-form Markdown notes.
+    #[serde(default)]
+    pub notes: String,
+    /// Makes the task come round again when it is completed.
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// Minutes before `due_time` to be reminded at, instead of the configured
+    /// ones. `Some` of an empty list turns reminders off for the task.
+    #[serde(default)]
+    pub reminders: Option<Vec<u32>>,
+    /// When a snoozed reminder comes back.
+    #[serde(default)]
+    pub snoozed_until: Option<NaiveDateTime>,
+    /// When the task was added. Unknown for tasks from before timestamps.
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// When the task was last edited or moved to another column.
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Task {
+    /// How many checklist items are done, out of how many, or `None` if the
+    /// task has no checklist.
+    pub fn checklist_progress(&self) -> Option<(usize, usize)> {
+        if self.checklist.is_empty() {
+            return None;
+        }
+        let done = self.checklist.iter().filter(|item| item.done).count();
+        Some((done, self.checklist.len()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl ChecklistItem {
+    pub fn new(text: &str) -> Self {
+        ChecklistItem {
+            text: text.trim().to_string(),
+            done: false,
+        }
+    }
+}
@@ -0,0 +1,803 @@
+//! The quick-add line.
+//!
+//! A line such as `Plan trip #travel #p1 #fri_5pm | Book flights` is read
+//! in two steps. [`tokenize_task_description`] splits it into
+//! [`Token`]s, each with the byte range it came from, and reports what is
+//! wrong with it as [`SyntaxError`]s. The grammar is:
+//!
+//! * words are separated by whitespace;
+//! * a word starting with `#` is, in this order, a due date such as
+//!   `#fri_5pm`, a priority (`#p1`–`#p3`, `#high`, `#medium`,
+//!   `#low`), a repeat rule (see [`Recurrence`]), `#until-YYYY-MM-DD` after
+//!   a repeat rule, or else a tag. The tag ends at the first character that
+//!   is not a letter, digit, `_` or `-`; the rest of the word belongs to
+//!   the description;
+//! * a word starting with `@` is a due date if it reads as one, and part of
+//!   the description otherwise;
+//! * everything after the first `|` is checklist items, one after each `|`;
+//! * a `\` before `#`, `@`, `|` or another `\` makes that character part of
+//!   the description, so `\#1 fan` is a task called `#1 fan`;
+//! * every other word is part of the description.
+//!
+//! [`parse_task_description`] then puts the tokens together into a
+//! [`ParsedTask`], and [`format_task_description`] writes a task back as a
+//! line that reads the same way.
+
+use std::fmt;
+use std::ops::Range;
+
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::dates::{DEFAULT_DUE_TIME, parse_due};
+use crate::model::{Priority, Task};
+use crate::recurrence::Recurrence;
+
+/// Characters a `\` takes literally.
+const ESCAPABLE: [char; 4] = ['#', '@', '|', '\\'];
+
+/// What a part of a quick-add line means.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// A word of the description, with escapes removed.
+    Word(String),
+    /// A tag, lowercase and without its `#`.
+    Tag(String),
+    Due(NaiveDateTime),
+    Priority(Priority),
+    Repeat(Recurrence),
+    /// `#until-…`, the last day of the line's repeat rule.
+    Until(NaiveDate),
+    /// A checklist item, with escapes removed. Its range starts at the `|`.
+    ChecklistItem(String),
+}
+
+/// A part of a quick-add line and the byte range of the line it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntaxErrorKind {
+    /// A tag that looks like a due date but is not a valid one, such as
+    /// `#2026-13-01` or `#fri_25:00`.
+    InvalidDueDate,
+    /// A due date after the first one.
+    ExtraDueDate,
+    /// A priority after the first one.
+    ExtraPriority,
+    /// A repeat rule or `#until-…` after the first one.
+    ExtraRepeatRule,
+}
+
+/// A mistake in a quick-add line. The line can still be parsed: invalid due
+/// dates are left in the description and only the first of each kind of
+/// tag counts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub kind: SyntaxErrorKind,
+    /// Byte range of the offending tag in the line.
+    pub span: Range<usize>,
+    /// The offending tag.
+    pub text: String,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            SyntaxErrorKind::InvalidDueDate => write!(
+                f,
+                "'{}' is not a due date (e.g. #2026-10-20_17:00 or #fri_5pm)",
+                self.text
+            ),
+            SyntaxErrorKind::ExtraDueDate => write!(
+                f,
+                "'{}' is a second due date; give the day and time in one tag, as in #fri_5pm",
+                self.text
+            ),
+            SyntaxErrorKind::ExtraPriority => {
+                write!(f, "'{}' is a second priority", self.text)
+            }
+            SyntaxErrorKind::ExtraRepeatRule => {
+                write!(f, "'{}' is a second repeat rule", self.text)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+/// The parts of a quick-add line, as read by [`parse_task_description`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedTask {
+    pub description: String,
+    /// Lowercase, without the `#`, each only once.
+    pub tags: Vec<String>,
+    pub due_time: Option<NaiveDateTime>,
+    /// `None` when the line has no priority tag.
+    pub priority: Option<Priority>,
+    /// Checklist items, from after each `|`.
+    pub checklist: Vec<String>,
+    pub recurrence: Option<Recurrence>,
+}
+
+/// Splits a quick-add line such as `Buy milk #home #errand #P2 #tomorrow_10am`
+/// into its description, tags, due time, priority, checklist and repeat
+/// rule. Due dates are resolved against the local clock; see
+/// [`parse_task_description_at`].
+///
+/// A repeat tag such as `#every-mon` (see [`Recurrence`]) makes the task
+/// recurring; without a due date it is due on the rule's first day.
+///
+/// Checklist items follow the task after `|`, as in
+/// `Plan trip #travel | Book flights | Reserve hotel`; tags are only read
+/// before the first `|`.
+///
+/// Mistakes are skipped over; use [`check_task_description`] to find them
+/// first.
+pub fn parse_task_description(description: &str) -> ParsedTask {
+    parse_task_description_at(description, Local::now().naive_local(), DEFAULT_DUE_TIME)
+}
+
+/// Like [`parse_task_description`], resolving relative due dates such as
+/// `#tomorrow`, `#fri` or `#in3d` against `now`. Due dates given without a
+/// time of day are due at `default_time`.
+pub fn parse_task_description_at(
+    description: &str,
+    now: NaiveDateTime,
+    default_time: NaiveTime,
+) -> ParsedTask {
+    let (tokens, _) = tokenize_task_description(description, now, default_time);
+    let mut parsed = ParsedTask::default();
+    let mut words = Vec::new();
+    let mut until = None;
+    for token in tokens {
+        match token.kind {
+            TokenKind::Word(word) => words.push(word),
+            TokenKind::Tag(tag) => {
+                if !parsed.tags.contains(&tag) {
+                    parsed.tags.push(tag);
+                }
+            }
+            TokenKind::Due(due) => {
+                parsed.due_time.get_or_insert(due);
+            }
+            TokenKind::Priority(priority) => {
+                parsed.priority.get_or_insert(priority);
+            }
+            TokenKind::Repeat(rule) => {
+                parsed.recurrence.get_or_insert(rule);
+            }
+            TokenKind::Until(date) => {
+                until.get_or_insert(date);
+            }
+            TokenKind::ChecklistItem(item) => parsed.checklist.push(item),
+        }
+    }
+    parsed.description = words.join(" ");
+    if let Some(rule) = &mut parsed.recurrence {
+        rule.until = until;
+        if parsed.due_time.is_none() {
+            parsed.due_time = rule.first_due(now.date(), default_time);
+        }
+    }
+    parsed
+}
+
+/// The first mistake in a quick-add line, if any, so it can be reported
+/// instead of being skipped over.
+pub fn check_task_description(line: &str) -> Result<(), SyntaxError> {
+    let (_, errors) = tokenize_task_description(line, Local::now().naive_local(), DEFAULT_DUE_TIME);
+    match errors.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Splits a quick-add line into its tokens, in order, and the mistakes in
+/// it, also in order. Whitespace and the `\` of escapes are not part of any
+/// token. Due dates are resolved as in [`parse_task_description_at`].
+pub fn tokenize_task_description(
+    line: &str,
+    now: NaiveDateTime,
+    default_time: NaiveTime,
+) -> (Vec<Token>, Vec<SyntaxError>) {
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    let mut sections = split_unescaped(line, '|').into_iter();
+    if let Some(head) = sections.next() {
+        for span in words(line, head) {
+            tokenize_word(line, span, now, default_time, &mut tokens, &mut errors);
+        }
+    }
+    for section in sections {
+        let item = unescape(line[section.clone()].trim());
+        if !item.is_empty() {
+            tokens.push(Token {
+                kind: TokenKind::ChecklistItem(item),
+                // Include the `|` before the item
+                span: section.start - 1..section.start + line[section].trim_end().len(),
+            });
+        }
+    }
+
+    // `#until-…` is a tag without a repeat rule to end
+    if !tokens
+        .iter()
+        .any(|t| matches!(t.kind, TokenKind::Repeat(_)))
+    {
+        for token in &mut tokens {
+            if let TokenKind::Until(_) = token.kind {
+                token.kind =
+                    TokenKind::Tag(line[token.span.start + 1..token.span.end].to_lowercase());
+            }
+        }
+    }
+
+    // Only the first of each counts
+    let mut seen = [false; 3];
+    for token in &tokens {
+        let (slot, kind) = match token.kind {
+            TokenKind::Due(_) => (0, SyntaxErrorKind::ExtraDueDate),
+            TokenKind::Priority(_) => (1, SyntaxErrorKind::ExtraPriority),
+            TokenKind::Repeat(_) => (2, SyntaxErrorKind::ExtraRepeatRule),
+            _ => continue,
+        };
+        if std::mem::replace(&mut seen[slot], true) {
+            errors.push(SyntaxError {
+                kind,
+                span: token.span.clone(),
+                text: line[token.span.clone()].to_string(),
+            });
+        }
+    }
+    let mut untils = tokens
+        .iter()
+        .filter(|t| matches!(t.kind, TokenKind::Until(_)));
+    untils.next();
+    for token in untils {
+        errors.push(SyntaxError {
+            kind: SyntaxErrorKind::ExtraRepeatRule,
+            span: token.span.clone(),
+            text: line[token.span.clone()].to_string(),
+        });
+    }
+    errors.sort_by_key(|error| error.span.start);
+    (tokens, errors)
+}
+
+/// Reads one whitespace-separated word of the part before the first `|`.
+fn tokenize_word(
+    line: &str,
+    span: Range<usize>,
+    now: NaiveDateTime,
+    default_time: NaiveTime,
+    tokens: &mut Vec<Token>,
+    errors: &mut Vec<SyntaxError>,
+) {
+    lazy_static! {
+        /// Starts like a date, or has a time of day in it.
+        static ref DATE_LIKE_RE: Regex = Regex::new(r"^(\d{4}-|.*\d:)").unwrap();
+    }
+    let word = &line[span.clone()];
+    let Some(sigil) = word.chars().next().filter(|&c| c == '#' || c == '@') else {
+        tokens.push(Token {
+            kind: TokenKind::Word(unescape(word)),
+            span,
+        });
+        return;
+    };
+    let body = &word[1..];
+    let is_tag_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    // Due dates may have a time of day in them, tags may not
+    let due_len = body
+        .find(|c: char| !is_tag_char(c) && c != ':')
+        .unwrap_or(body.len());
+    let tag_len = body.find(|c: char| !is_tag_char(c)).unwrap_or(body.len());
+
+    let (kind, len) = if let Some(due) = parse_due(&body[..due_len], now, default_time) {
+        (TokenKind::Due(due), due_len)
+    } else if due_len > 0 && DATE_LIKE_RE.is_match(&body[..due_len]) {
+        errors.push(SyntaxError {
+            kind: SyntaxErrorKind::InvalidDueDate,
+            span: span.start..span.start + 1 + due_len,
+            text: word[..1 + due_len].to_string(),
+        });
+        (TokenKind::Word(unescape(word)), body.len())
+    } else if sigil == '@' || tag_len == 0 {
+        (TokenKind::Word(unescape(word)), body.len())
+    } else {
+        let tag = &body[..tag_len];
+        let kind = if let Some(priority) = parse_priority(tag) {
+            TokenKind::Priority(priority)
+        } else if let Some(rule) = Recurrence::from_tag(tag) {
+            TokenKind::Repeat(rule)
+        } else if let Some(date) = tag
+            .strip_prefix("until-")
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        {
+            TokenKind::Until(date)
+        } else {
+            TokenKind::Tag(tag.to_lowercase())
+        };
+        (kind, tag_len)
+    };
+    let end = span.start + 1 + len;
+    tokens.push(Token {
+        kind,
+        span: span.start..end,
+    });
+    // Whatever follows the tag in the same word, such as a comma
+    if end < span.end {
+        tokens.push(Token {
+            kind: TokenKind::Word(unescape(&line[end..span.end])),
+            span: end..span.end,
+        });
+    }
+}
+
+fn parse_priority(tag: &str) -> Option<Priority> {
+    match tag.to_lowercase().as_str() {
+        "p1" | "high" => Some(Priority::High),
+        "p2" | "medium" => Some(Priority::Medium),
+        "p3" | "low" => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+/// Byte ranges of the parts of `line` between unescaped `separator`s.
+pub(crate) fn split_unescaped(line: &str, separator: char) -> Vec<Range<usize>> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            // Skip the escaped character
+            chars.next();
+        } else if c == separator {
+            sections.push(start..i);
+            start = i + c.len_utf8();
+        }
+    }
+    sections.push(start..line.len());
+    sections
+}
+
+/// Byte ranges of the whitespace-separated words of `line[section]`.
+fn words(line: &str, section: Range<usize>) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in line[section.clone()].char_indices() {
+        let i = section.start + i;
+        match (c.is_whitespace(), start) {
+            (true, Some(word_start)) => {
+                words.push(word_start..i);
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        words.push(word_start..section.end);
+    }
+    words
+}
+
+/// Removes the `\` before escaped characters.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(&next) if c == '\\' && ESCAPABLE.contains(&next) => {
+                unescaped.push(next);
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Writes a description word so it reads back as that word: escapes
+/// backslashes and `|`, and a `#` or `@` the word would otherwise be taken
+/// for a tag or due date by.
+fn escape_word(word: &str) -> String {
+    let mut escaped = String::with_capacity(word.len());
+    let mut chars = word.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '|' || (c == '\\' && chars.peek().is_some_and(|next| ESCAPABLE.contains(next))) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    if word.starts_with(['#', '@']) {
+        let (tokens, errors) =
+            tokenize_task_description(&escaped, NaiveDateTime::default(), DEFAULT_DUE_TIME);
+        let plain =
+            matches!(tokens.as_slice(), [Token { kind: TokenKind::Word(w), .. }] if w == word);
+        if !plain || !errors.is_empty() {
+            escaped.insert(0, '\\');
+        }
+    }
+    escaped
+}
+
+/// Writes a task's description, tags, due time, repeat rule and (unless it
+/// is the default) priority as a quick-add line that [`parse_task_description`]
+/// reads back the same way. The checklist is left out, since the line
+/// cannot say which items are done.
+pub fn format_task_description(task: &Task) -> String {
+    let mut words: Vec<String> = task
+        .description
+        .split_whitespace()
+        .map(escape_word)
+        .collect();
+    for tag in &task.tags {
+        words.push(format!("#{}", tag));
+    }
+    match task.priority {
+        Priority::High => words.push("#p1".to_string()),
+        Priority::Low => words.push("#p3".to_string()),
+        Priority::Medium => {}
+    }
+    if let Some(due_time) = &task.due_time {
+        words.push(format!("#{}", due_time.format("%Y-%m-%d_%H:%M")));
+    }
+    if let Some(recurrence) = &task.recurrence {
+        for tag in recurrence.to_tags() {
+            words.push(format!("#{}", tag));
+        }
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Weekday};
+    use proptest::prelude::*;
+
+    use crate::recurrence::Frequency;
+    use crate::testing::{now, task};
+
+    fn parse(line: &str) -> (String, Vec<String>, Option<NaiveDateTime>) {
+        let parsed = parse_task_description_at(line, now(), DEFAULT_DUE_TIME);
+        (parsed.description, parsed.tags, parsed.due_time)
+    }
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> Option<NaiveDateTime> {
+        NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, 0)
+    }
+
+    fn check(line: &str) -> Result<(), (SyntaxErrorKind, String)> {
+        check_task_description(line).map_err(|error| (error.kind, error.text))
+    }
+
+    #[test]
+    fn at_sign_dates_are_removed_from_the_description() {
+        assert_eq!(
+            parse("Call the bank @2026-10-20"),
+            ("Call the bank".to_string(), vec![], at(2026, 10, 20, 9, 0))
+        );
+    }
+
+    #[test]
+    fn natural_dates_are_not_taken_for_tags() {
+        assert_eq!(
+            parse("Buy milk #home #tomorrow"),
+            (
+                "Buy milk".to_string(),
+                vec!["home".to_string()],
+                at(2026, 10, 15, 9, 0)
+            )
+        );
+        assert_eq!(
+            parse("Report #work #next-week_2:30pm"),
+            (
+                "Report".to_string(),
+                vec!["work".to_string()],
+                at(2026, 10, 19, 14, 30)
+            )
+        );
+    }
+
+    #[test]
+    fn tag_before_full_date() {
+        assert_eq!(
+            parse("Ship #work #2026-10-20_17:00"),
+            (
+                "Ship".to_string(),
+                vec!["work".to_string()],
+                at(2026, 10, 20, 17, 0)
+            )
+        );
+    }
+
+    #[test]
+    fn at_signs_that_are_not_dates_stay() {
+        assert_eq!(
+            parse("Email @bob #in3d"),
+            ("Email @bob".to_string(), vec![], at(2026, 10, 17, 9, 0))
+        );
+    }
+
+    #[test]
+    fn every_hashtag_is_kept() {
+        assert_eq!(
+            parse("Fix login #work #Urgent #work"),
+            (
+                "Fix login".to_string(),
+                vec!["work".to_string(), "urgent".to_string()],
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn priority_words_only_match_whole_tags() {
+        let parsed = parse_task_description("Drive #highway #p1");
+        assert_eq!(parsed.tags, vec!["highway".to_string()]);
+        assert_eq!(parsed.priority, Some(Priority::High));
+        let parsed = parse_task_description("Wave #high-five");
+        assert_eq!(parsed.tags, vec!["high-five".to_string()]);
+        assert_eq!(parsed.priority, None);
+    }
+
+    #[test]
+    fn repeat_rules_are_not_taken_for_tags() {
+        let parsed = parse_task_description_at(
+            "Standup #work #every-mon-thu #until-2026-12-31",
+            now(),
+            DEFAULT_DUE_TIME,
+        );
+        assert_eq!(
+            parse("Standup #work #every-mon-thu #until-2026-12-31"),
+            (
+                "Standup".to_string(),
+                vec!["work".to_string()],
+                // The first Monday or Thursday
+                at(2026, 10, 15, 9, 0)
+            )
+        );
+        assert_eq!(
+            parsed.recurrence.unwrap().to_tags(),
+            ["every-mon-thu", "until-2026-12-31"]
+        );
+
+        // Without a rule, `#until-…` is an ordinary tag
+        let parsed = parse_task_description_at("Trip #until-2026-12-31", now(), DEFAULT_DUE_TIME);
+        assert_eq!(parsed.tags, vec!["until-2026-12-31".to_string()]);
+        assert_eq!(parsed.recurrence, None);
+    }
+
+    #[test]
+    fn tokens_cover_their_text() {
+        let line = "Plan trip, #travel, #P1 @fri_5pm \\#1 | Book flights |  | Pack ";
+        let (tokens, errors) = tokenize_task_description(line, now(), DEFAULT_DUE_TIME);
+        assert!(errors.is_empty());
+        let spans: Vec<(&str, TokenKind)> = tokens
+            .into_iter()
+            .map(|token| (&line[token.span], token.kind))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                ("Plan", TokenKind::Word("Plan".to_string())),
+                ("trip,", TokenKind::Word("trip,".to_string())),
+                ("#travel", TokenKind::Tag("travel".to_string())),
+                (",", TokenKind::Word(",".to_string())),
+                ("#P1", TokenKind::Priority(Priority::High)),
+                ("@fri_5pm", TokenKind::Due(at(2026, 10, 16, 17, 0).unwrap())),
+                ("\\#1", TokenKind::Word("#1".to_string())),
+                (
+                    "| Book flights",
+                    TokenKind::ChecklistItem("Book flights".to_string())
+                ),
+                ("| Pack", TokenKind::ChecklistItem("Pack".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn escapes_keep_characters_in_the_description() {
+        let parsed = parse_task_description_at(
+            r"Fix \#12 \@home a\|b C:\temp \\#x | x\|y",
+            now(),
+            DEFAULT_DUE_TIME,
+        );
+        assert_eq!(parsed.description, r"Fix #12 @home a|b C:\temp \#x");
+        assert!(parsed.tags.is_empty());
+        assert_eq!(parsed.checklist, ["x|y"]);
+        // A `#` inside a word was never a tag
+        assert_eq!(parse("Learn C# today").0, "Learn C# today");
+    }
+
+    #[test]
+    fn mistakes_are_found() {
+        assert_eq!(
+            check("Pay rent #2026-13-01"),
+            Err((SyntaxErrorKind::InvalidDueDate, "#2026-13-01".to_string()))
+        );
+        assert_eq!(
+            check("Pay rent #fri_25:00"),
+            Err((SyntaxErrorKind::InvalidDueDate, "#fri_25:00".to_string()))
+        );
+        assert_eq!(check("Pay rent #home #fri_5pm"), Ok(()));
+        assert_eq!(check("Pay rent | #2026-13-01"), Ok(()));
+        assert_eq!(
+            check("Call #fri #5pm #p1 #low"),
+            Err((SyntaxErrorKind::ExtraDueDate, "#5pm".to_string()))
+        );
+        assert_eq!(
+            check("Call #p1 #low"),
+            Err((SyntaxErrorKind::ExtraPriority, "#low".to_string()))
+        );
+        assert_eq!(
+            check("Gym #daily #every-mon"),
+            Err((SyntaxErrorKind::ExtraRepeatRule, "#every-mon".to_string()))
+        );
+
+        // Parsing goes on past mistakes, keeping the first of each
+        let parsed =
+            parse_task_description_at("Call #fri #5pm #2026-13-01", now(), DEFAULT_DUE_TIME);
+        assert_eq!(parsed.description, "Call #2026-13-01");
+        assert_eq!(parsed.due_time, at(2026, 10, 16, 9, 0));
+        let (_, errors) =
+            tokenize_task_description("Call #fri #5pm #2026-13-01", now(), DEFAULT_DUE_TIME);
+        assert_eq!(
+            errors.iter().map(|e| e.span.clone()).collect::<Vec<_>>(),
+            [10..14, 15..26]
+        );
+    }
+
+    #[test]
+    fn formatted_descriptions_are_escaped() {
+        let task = Task {
+            tags: vec!["work".to_string()],
+            ..task("#1 fan @home @fri a|b \\#x")
+        };
+        let line = format_task_description(&task);
+        assert_eq!(line, r"\#1 fan @home \@fri a\|b \\#x #work");
+        let parsed = parse_task_description_at(&line, now(), DEFAULT_DUE_TIME);
+        assert_eq!(parsed.description, task.description);
+        assert_eq!(parsed.tags, task.tags);
+        assert_eq!(parsed.due_time, None);
+    }
+
+    fn task_from(line: &str) -> Task {
+        let parsed = parse_task_description_at(line, now(), DEFAULT_DUE_TIME);
+        Task {
+            tags: parsed.tags,
+            priority: parsed.priority.unwrap_or_default(),
+            due_time: parsed.due_time,
+            recurrence: parsed.recurrence,
+            ..task(&parsed.description)
+        }
+    }
+
+    /// A tag the parser reads back as a tag.
+    fn is_plain_tag(tag: &str) -> bool {
+        let (tokens, _) = tokenize_task_description(&format!("#{}", tag), now(), DEFAULT_DUE_TIME);
+        tokens.len() == 1
+            && tokens[0].kind == TokenKind::Tag(tag.to_string())
+            && !tag.starts_with("until-")
+    }
+
+    fn weekday_strategy() -> impl Strategy<Value = Vec<Weekday>> {
+        proptest::sample::subsequence(
+            vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ],
+            0..=7,
+        )
+    }
+
+    fn date_strategy() -> impl Strategy<Value = NaiveDate> {
+        (0i64..40_000)
+            .prop_map(|days| NaiveDate::from_ymd_opt(2000, 1, 1).unwrap() + Duration::days(days))
+    }
+
+    fn recurrence_strategy() -> impl Strategy<Value = Recurrence> {
+        (
+            prop_oneof![
+                Just(Frequency::Daily),
+                Just(Frequency::Weekly),
+                Just(Frequency::Monthly),
+                Just(Frequency::Yearly),
+            ],
+            1u32..1000,
+            weekday_strategy(),
+            proptest::option::of(1u32..=12),
+            proptest::option::of(1u32..=31),
+            proptest::option::of(date_strategy()),
+        )
+            .prop_map(|(frequency, interval, weekdays, month, month_day, until)| {
+                Recurrence {
+                    frequency,
+                    interval,
+                    weekdays: if frequency == Frequency::Weekly {
+                        weekdays
+                    } else {
+                        Vec::new()
+                    },
+                    month: month.filter(|_| frequency == Frequency::Yearly),
+                    month_day: month_day
+                        .filter(|_| matches!(frequency, Frequency::Monthly | Frequency::Yearly)),
+                    until,
+                }
+            })
+    }
+
+    prop_compose! {
+        fn task_strategy()(
+            words in proptest::collection::vec(r"[a-zA-Z0-9#@|\\,.:_-]{1,10}", 0..6),
+            tags in proptest::collection::vec("[a-z][a-z0-9_-]{0,10}", 0..4),
+            priority in prop_oneof![Just(Priority::Low), Just(Priority::Medium), Just(Priority::High)],
+            date in date_strategy(),
+            minutes in 0u32..24 * 60,
+            has_due in any::<bool>(),
+            recurrence in proptest::option::of(recurrence_strategy()),
+        ) -> Task {
+            let mut task = task(&words.join(" "));
+            for tag in tags {
+                if is_plain_tag(&tag) && !task.tags.contains(&tag) {
+                    task.tags.push(tag);
+                }
+            }
+            task.priority = priority;
+            // A recurring task always has a due date
+            if has_due || recurrence.is_some() {
+                task.due_time = date.and_hms_opt(minutes / 60, minutes % 60, 0);
+            }
+            task.recurrence = recurrence;
+            task
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn formatted_tasks_read_back_the_same(task in task_strategy()) {
+            let line = format_task_description(&task);
+            prop_assert_eq!(check_task_description(&line), Ok(()));
+            let parsed = parse_task_description_at(&line, now(), DEFAULT_DUE_TIME);
+            prop_assert_eq!(&parsed.description, &task.description);
+            prop_assert_eq!(&parsed.tags, &task.tags);
+            prop_assert_eq!(parsed.priority.unwrap_or_default(), task.priority);
+            prop_assert_eq!(parsed.due_time, task.due_time);
+            prop_assert_eq!(&parsed.recurrence, &task.recurrence);
+            prop_assert!(parsed.checklist.is_empty());
+        }
+
+        #[test]
+        fn tokens_are_ordered_slices_of_the_line(line in r"[a-z0-9 #@|\\:_,é-]{0,40}") {
+            let (tokens, errors) = tokenize_task_description(&line, now(), DEFAULT_DUE_TIME);
+            let mut end = 0;
+            for token in &tokens {
+                prop_assert!(end <= token.span.start && token.span.start < token.span.end);
+                prop_assert!(line.get(token.span.clone()).is_some());
+                end = token.span.end;
+            }
+            prop_assert!(end <= line.len());
+            for error in &errors {
+                prop_assert_eq!(line.get(error.span.clone()), Some(error.text.as_str()));
+            }
+        }
+
+        #[test]
+        fn formatting_a_parsed_line_is_stable(line in r"[a-z0-9 #@|\\:_-]{0,40}") {
+            let formatted = format_task_description(&task_from(&line));
+            prop_assert_eq!(format_task_description(&task_from(&formatted)), formatted);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Where the app keeps its files, following the XDG base directory spec.
+//!
+//! Task data lives in `$XDG_DATA_HOME/todo-gui` (default
+//! `~/.local/share/todo-gui`) and configuration in `$XDG_CONFIG_HOME/todo-gui`
+//! (default `~/.config/todo-gui`).
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::StorageError;
+
+/// Directory name used below the XDG base directories.
+pub const APP_DIR_NAME: &str = "todo-gui";
+/// Name of the task file inside the data directory.
+pub const DATA_FILE_NAME: &str = "todo_data.json";
+/// Environment variable that overrides the task file location.
+pub const DATA_FILE_ENV: &str = "TODO_GUI_FILE";
+
+/// `$XDG_DATA_HOME/todo-gui`, or `None` if neither it nor `$HOME` is set.
+pub fn data_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share").map(|dir| dir.join(APP_DIR_NAME))
+}
+
+/// `$XDG_CONFIG_HOME/todo-gui`, or `None` if neither it nor `$HOME` is set.
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join(APP_DIR_NAME))
+}
+
+/// [`data_dir`], or the working directory when there is no home directory
+/// to put it in.
+pub fn data_dir_or_cwd() -> PathBuf {
+    data_dir().unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// The task file of the default board.
+pub fn default_data_file() -> PathBuf {
+    data_dir_or_cwd().join(DATA_FILE_NAME)
+}
+
+/// A task file given explicitly (e.g. with `--file`) or through
+/// [`DATA_FILE_ENV`]. When there is one, boards are bypassed and only that
+/// file is used.
+pub fn data_file_override(explicit: Option<PathBuf>) -> Option<PathBuf> {
+    explicit.or_else(|| {
+        env::var_os(DATA_FILE_ENV)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// Older versions kept `todo_data.json` in whatever directory the app was
+/// started from. If `data_file` is the default location and does not exist
+/// yet, such a file is copied there once. Returns the path that was copied.
+pub fn migrate_legacy_data_file(data_file: &Path) -> Result<Option<PathBuf>, StorageError> {
+    let legacy = PathBuf::from(DATA_FILE_NAME);
+    if data_file != default_data_file() || data_file.exists() || !legacy.is_file() {
+        return Ok(None);
+    }
+    if let Some(dir) = data_file.parent() {
+        fs::create_dir_all(dir).map_err(StorageError::io(dir))?;
+    }
+    fs::copy(&legacy, data_file).map_err(StorageError::io(data_file))?;
+    Ok(Some(legacy))
+}
+
+fn xdg_dir(var: &str, home_relative: &str) -> Option<PathBuf> {
+    // The spec says relative paths in these variables must be ignored.
+    env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| {
+            env::var_os("HOME")
+                .filter(|home| !home.is_empty())
+                .map(|home| PathBuf::from(home).join(home_relative))
+        })
+}
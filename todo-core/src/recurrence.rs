@@ -0,0 +1,747 @@
+//! Repeat rules for recurring tasks.
+//!
+//! A rule is stored as an RFC 5545 `RRULE` value such as
+//! `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;UNTIL=20261231`, using the `FREQ`,
+//! `INTERVAL`, `BYDAY` (weekly rules), `BYMONTH` (yearly rules),
+//! `BYMONTHDAY` (monthly and yearly rules) and `UNTIL` parts. In the quick-add line it is written as a `#` tag:
+//!
+//! * `#daily`, `#weekly`, `#monthly` or `#yearly`;
+//! * `#every-day`, `#every-3-days`, `#every-2-weeks`, `#every-month`,
+//!   `#every-year`;
+//! * weekdays: `#every-mon`, `#every-mon-thu`, `#every-2-weeks-fri`, or
+//!   `#every-weekday` for Monday to Friday;
+//! * a day of the month: `#every-1st`, `#every-3-months-15th`;
+//! * a date: `#every-feb-29th`, `#every-2-years-dec-25th`, or just the
+//!   month or day as in `#every-mar` and `#every-year-15th`;
+//!
+//! optionally followed by `#until-2026-12-31` for the last day it may fall
+//! on.
+//!
+//! Monthly rules on a day that a month does not have, such as the 31st,
+//! fall on the last day of that month instead of skipping it, and so do
+//! yearly rules on 29 February.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Days, Month, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// When a recurring task comes round again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    /// Repeat every `interval` days, weeks, months or years; at least 1.
+    pub interval: u32,
+    /// Days of the week a weekly rule falls on, Monday first. Empty means
+    /// the weekday of the task's due date.
+    pub weekdays: Vec<Weekday>,
+    /// Month a yearly rule falls in, 1 to 12. `None` means the month of the
+    /// task's due date.
+    pub month: Option<u32>,
+    /// Day of the month a monthly or yearly rule falls on. `None` means the
+    /// day of the task's due date.
+    pub month_day: Option<u32>,
+    /// Last day an occurrence may fall on.
+    pub until: Option<NaiveDate>,
+}
+
+/// Months as written in repeat tags, January first.
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+impl Recurrence {
+    pub fn new(frequency: Frequency) -> Self {
+        Recurrence {
+            frequency,
+            interval: 1,
+            weekdays: Vec::new(),
+            month: None,
+            month_day: None,
+            until: None,
+        }
+    }
+
+    /// Reads a repeat tag without its `#`, such as `every-mon` or
+    /// `monthly`. `None` if it is not one; `#until-…` is handled by the
+    /// parser.
+    pub(crate) fn from_tag(tag: &str) -> Option<Self> {
+        lazy_static! {
+            static ref EVERY_RE: Regex = Regex::new(
+                r"^every-(?:(\d{1,3})-)?(?:(day|week|month|year)s?|(weeks?)-([a-z-]+)|(months?)-(\d{1,2})(?:st|nd|rd|th)|(\d{1,2})(?:st|nd|rd|th)|(weekday|[a-z]+(?:-[a-z]+)*))$"
+            )
+            .unwrap();
+            static ref EVERY_DATE_RE: Regex = Regex::new(
+                r"^every-(?:(?:(\d{1,3})-years?-)?(jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)(?:-(\d{1,2})(?:st|nd|rd|th))?|(?:(\d{1,3})-)?years?-(\d{1,2})(?:st|nd|rd|th))$"
+            )
+            .unwrap();
+        }
+        let tag = tag.to_lowercase();
+        let simple = match tag.as_str() {
+            "daily" => Some(Frequency::Daily),
+            "weekly" => Some(Frequency::Weekly),
+            "monthly" => Some(Frequency::Monthly),
+            "yearly" | "annually" => Some(Frequency::Yearly),
+            _ => None,
+        };
+        if let Some(frequency) = simple {
+            return Some(Recurrence::new(frequency));
+        }
+        if let Some(captures) = EVERY_DATE_RE.captures(&tag) {
+            let month = match captures.get(2) {
+                Some(name) => Some(MONTHS.iter().position(|&m| m == name.as_str())? as u32 + 1),
+                None => None,
+            };
+            let month_day = match captures.get(3).or_else(|| captures.get(5)) {
+                Some(day) => Some(day.as_str().parse().ok().filter(|d| (1..=31).contains(d))?),
+                None => None,
+            };
+            let interval = match captures.get(1).or_else(|| captures.get(4)) {
+                Some(count) => count.as_str().parse().ok().filter(|&n| n > 0)?,
+                None => 1,
+            };
+            return Some(Recurrence {
+                interval,
+                month,
+                month_day,
+                ..Recurrence::new(Frequency::Yearly)
+            });
+        }
+
+        let captures = EVERY_RE.captures(&tag)?;
+        let interval = match captures.get(1) {
+            Some(count) => count.as_str().parse().ok().filter(|&n| n > 0)?,
+            None => 1,
+        };
+        let mut rule = if let Some(unit) = captures.get(2) {
+            Recurrence::new(match unit.as_str() {
+                "day" => Frequency::Daily,
+                "week" => Frequency::Weekly,
+                "month" => Frequency::Monthly,
+                _ => Frequency::Yearly,
+            })
+        } else if let Some(days) = captures.get(4) {
+            Recurrence {
+                weekdays: parse_weekdays(days.as_str())?,
+                ..Recurrence::new(Frequency::Weekly)
+            }
+        } else if let Some(day) = captures.get(6).or_else(|| captures.get(7)) {
+            if captures.get(7).is_some() && captures.get(1).is_some() {
+                // `every-2-15th` says neither months nor weeks
+                return None;
+            }
+            Recurrence {
+                month_day: Some(day.as_str().parse().ok().filter(|d| (1..=31).contains(d))?),
+                ..Recurrence::new(Frequency::Monthly)
+            }
+        } else {
+            let days = captures.get(8)?.as_str();
+            if captures.get(1).is_some() {
+                // Intervals of weekdays need `weeks`, as in `every-2-weeks-mon`
+                return None;
+            }
+            Recurrence {
+                weekdays: parse_weekdays(days)?,
+                ..Recurrence::new(Frequency::Weekly)
+            }
+        };
+        rule.interval = interval;
+        Some(rule)
+    }
+
+    /// The rule as quick-add tags without their `#`, which
+    /// [`from_tag`](Self::from_tag) reads back the same way.
+    pub fn to_tags(&self) -> Vec<String> {
+        let unit = match self.frequency {
+            Frequency::Daily => "day",
+            Frequency::Weekly => "week",
+            Frequency::Monthly => "month",
+            Frequency::Yearly => "year",
+        };
+        let mut tag = if self.interval == 1 {
+            "every".to_string()
+        } else {
+            format!("every-{}-{}s", self.interval, unit)
+        };
+        match (self.frequency, self.month_day) {
+            (Frequency::Weekly, _) if !self.weekdays.is_empty() => {
+                for weekday in &self.weekdays {
+                    tag.push('-');
+                    tag.push_str(&weekday.to_string().to_lowercase());
+                }
+            }
+            (Frequency::Monthly, Some(day)) => {
+                tag.push_str(&format!("-{}{}", day, ordinal_suffix(day)));
+            }
+            (Frequency::Yearly, day) if self.month.is_some() || day.is_some() => {
+                match self.month {
+                    Some(month) => tag.push_str(&format!("-{}", MONTHS[month as usize - 1])),
+                    None if self.interval == 1 => tag.push_str("-year"),
+                    None => {}
+                }
+                if let Some(day) = day {
+                    tag.push_str(&format!("-{}{}", day, ordinal_suffix(day)));
+                }
+            }
+            _ if self.interval == 1 => {
+                tag = match self.frequency {
+                    Frequency::Daily => "daily",
+                    Frequency::Weekly => "weekly",
+                    Frequency::Monthly => "monthly",
+                    Frequency::Yearly => "yearly",
+                }
+                .to_string();
+            }
+            _ => {}
+        }
+        let mut tags = vec![tag];
+        if let Some(until) = self.until {
+            tags.push(format!("until-{}", until.format("%Y-%m-%d")));
+        }
+        tags
+    }
+
+    /// The rule as an RFC 5545 `RRULE` value.
+    pub fn to_rrule(&self) -> String {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        let mut rule = format!("FREQ={}", frequency);
+        if self.interval != 1 {
+            rule.push_str(&format!(";INTERVAL={}", self.interval));
+        }
+        if !self.weekdays.is_empty() {
+            let days: Vec<&str> = self.weekdays.iter().map(|&day| rrule_day(day)).collect();
+            rule.push_str(&format!(";BYDAY={}", days.join(",")));
+        }
+        if let Some(month) = self.month {
+            rule.push_str(&format!(";BYMONTH={}", month));
+        }
+        if let Some(day) = self.month_day {
+            rule.push_str(&format!(";BYMONTHDAY={}", day));
+        }
+        if let Some(until) = self.until {
+            rule.push_str(&format!(";UNTIL={}", until.format("%Y%m%d")));
+        }
+        rule
+    }
+
+    /// The first occurrence on or after `today`, at `time`, for a recurring
+    /// task added without a due date.
+    pub fn first_due(&self, today: NaiveDate, time: NaiveTime) -> Option<NaiveDateTime> {
+        let mut date = today;
+        if !self.weekdays.is_empty() {
+            while !self.weekdays.contains(&date.weekday()) {
+                date = date.succ_opt()?;
+            }
+        }
+        if let Some(month) = self.month {
+            let day = self.month_day.unwrap_or(today.day());
+            date = month_day_in(NaiveDate::from_ymd_opt(today.year(), month, 1)?, day);
+            if date < today {
+                date = month_day_in(NaiveDate::from_ymd_opt(today.year() + 1, month, 1)?, day);
+            }
+        } else if let Some(day) = self.month_day {
+            date = month_day_in(date.with_day(1)?, day);
+            if date < today {
+                date = month_day_in(date.with_day(1)?.checked_add_months(Months::new(1))?, day);
+            }
+        }
+        Some(date.and_time(time)).filter(|due| self.allows(due.date()))
+    }
+
+    /// The occurrence after the one due at `due` that falls after `today`,
+    /// so completing an overdue task does not leave the next one overdue
+    /// as well. `None` once the rule has ended.
+    pub fn next_due(&self, due: NaiveDateTime, today: NaiveDate) -> Option<NaiveDateTime> {
+        let mut next = self.step(due)?;
+        while next.date() <= today {
+            next = self.step(next)?;
+        }
+        Some(next).filter(|next| self.allows(next.date()))
+    }
+
+    /// Fills in the weekday, month or day of the month the rule takes from
+    /// a due date, so stepping from a shortened month end or from 28
+    /// February keeps the original day.
+    pub fn anchored_to(&self, due: NaiveDate) -> Self {
+        let mut rule = self.clone();
+        match rule.frequency {
+            Frequency::Weekly if rule.weekdays.is_empty() => rule.weekdays = vec![due.weekday()],
+            Frequency::Monthly if rule.month_day.is_none() => rule.month_day = Some(due.day()),
+            Frequency::Yearly => {
+                rule.month.get_or_insert(due.month());
+                rule.month_day.get_or_insert(due.day());
+            }
+            _ => {}
+        }
+        rule
+    }
+
+    fn allows(&self, date: NaiveDate) -> bool {
+        self.until.is_none_or(|until| date <= until)
+    }
+
+    /// The occurrence straight after `due`, ignoring `until`.
+    fn step(&self, due: NaiveDateTime) -> Option<NaiveDateTime> {
+        let date = due.date();
+        let next = match self.frequency {
+            Frequency::Daily => date.checked_add_days(Days::new(u64::from(self.interval)))?,
+            Frequency::Weekly if self.weekdays.is_empty() => {
+                date.checked_add_days(Days::new(7 * u64::from(self.interval)))?
+            }
+            Frequency::Weekly => {
+                let later_this_week = self
+                    .weekdays
+                    .iter()
+                    .map(|day| day.num_days_from_monday())
+                    .filter(|&day| day > date.weekday().num_days_from_monday())
+                    .min();
+                match later_this_week {
+                    Some(day) => date.checked_add_days(Days::new(u64::from(
+                        day - date.weekday().num_days_from_monday(),
+                    )))?,
+                    None => {
+                        let monday = date.checked_sub_days(Days::new(u64::from(
+                            date.weekday().num_days_from_monday(),
+                        )))?;
+                        let first = self.weekdays.iter().map(|day| day.num_days_from_monday());
+                        monday.checked_add_days(Days::new(
+                            7 * u64::from(self.interval) + u64::from(first.min()?),
+                        ))?
+                    }
+                }
+            }
+            Frequency::Monthly => {
+                let month = date
+                    .with_day(1)?
+                    .checked_add_months(Months::new(self.interval))?;
+                month_day_in(month, self.month_day.unwrap_or(date.day()))
+            }
+            Frequency::Yearly => {
+                // 29 February falls on the 28th in other years
+                let year = date
+                    .year()
+                    .checked_add(i32::try_from(self.interval).ok()?)?;
+                let month = self.month.unwrap_or(date.month());
+                month_day_in(
+                    NaiveDate::from_ymd_opt(year, month, 1)?,
+                    self.month_day.unwrap_or(date.day()),
+                )
+            }
+        };
+        Some(next.and_time(due.time()))
+    }
+}
+
+/// `day` of the month starting at `first`, or its last day if it is
+/// shorter.
+fn month_day_in(first: NaiveDate, day: u32) -> NaiveDate {
+    (1..=day)
+        .rev()
+        .find_map(|day| first.with_day(day))
+        .unwrap_or(first)
+}
+
+fn parse_weekdays(text: &str) -> Option<Vec<Weekday>> {
+    let mut weekdays = Vec::new();
+    if text == "weekday" {
+        weekdays.extend(&WEEKDAYS[..5]);
+    } else {
+        for name in text.split('-') {
+            weekdays.push(name.parse::<Weekday>().ok()?);
+        }
+    }
+    Some(sorted_weekdays(weekdays))
+}
+
+fn sorted_weekdays(mut weekdays: Vec<Weekday>) -> Vec<Weekday> {
+    weekdays.sort_by_key(|day| day.num_days_from_monday());
+    weekdays.dedup();
+    weekdays
+}
+
+fn ordinal_suffix(day: u32) -> &'static str {
+    match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+fn rrule_day(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    /// Reads an RFC 5545 `RRULE` value, with or without the `RRULE:`
+    /// prefix.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let text = text.strip_prefix("RRULE:").unwrap_or(text);
+        let mut frequency = None;
+        let mut rule = Recurrence::new(Frequency::Daily);
+        for part in text.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected NAME=VALUE, got \"{}\"", part))?;
+            match name.to_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(format!("unsupported frequency \"{}\"", value)),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or_else(|| format!("invalid interval \"{}\"", value))?
+                }
+                "BYDAY" => {
+                    let days = value.split(',').map(|day| {
+                        WEEKDAYS
+                            .into_iter()
+                            .find(|&weekday| rrule_day(weekday).eq_ignore_ascii_case(day))
+                            .ok_or_else(|| format!("invalid weekday \"{}\"", day))
+                    });
+                    rule.weekdays = sorted_weekdays(days.collect::<Result<_, _>>()?);
+                }
+                "BYMONTH" => {
+                    rule.month = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|month| (1..=12).contains(month))
+                            .ok_or_else(|| format!("invalid month \"{}\"", value))?,
+                    )
+                }
+                "BYMONTHDAY" => {
+                    rule.month_day = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|day| (1..=31).contains(day))
+                            .ok_or_else(|| format!("invalid day of the month \"{}\"", value))?,
+                    )
+                }
+                "UNTIL" => {
+                    let date = value.get(..8).unwrap_or(value);
+                    rule.until = Some(
+                        NaiveDate::parse_from_str(date, "%Y%m%d")
+                            .map_err(|_| format!("invalid end date \"{}\"", value))?,
+                    );
+                }
+                _ => return Err(format!("unsupported rule part \"{}\"", name)),
+            }
+        }
+        rule.frequency = frequency.ok_or("the rule has no FREQ")?;
+        if !rule.weekdays.is_empty() && rule.frequency != Frequency::Weekly {
+            return Err("BYDAY is only supported with FREQ=WEEKLY".to_string());
+        }
+        if rule.month.is_some() && rule.frequency != Frequency::Yearly {
+            return Err("BYMONTH is only supported with FREQ=YEARLY".to_string());
+        }
+        if rule.month_day.is_some()
+            && !matches!(rule.frequency, Frequency::Monthly | Frequency::Yearly)
+        {
+            return Err(
+                "BYMONTHDAY is only supported with FREQ=MONTHLY or FREQ=YEARLY".to_string(),
+            );
+        }
+        Ok(rule)
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(rule: Recurrence) -> Self {
+        rule.to_rrule()
+    }
+}
+
+impl fmt::Display for Recurrence {
+    /// Describes the rule for people, e.g. "every 2 weeks on Mon, Thu".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match self.frequency {
+            Frequency::Daily => "day",
+            Frequency::Weekly => "week",
+            Frequency::Monthly => "month",
+            Frequency::Yearly => "year",
+        };
+        if self.interval == 1 {
+            write!(f, "every {}", unit)?;
+        } else {
+            write!(f, "every {} {}s", self.interval, unit)?;
+        }
+        if !self.weekdays.is_empty() {
+            let days: Vec<String> = self.weekdays.iter().map(Weekday::to_string).collect();
+            write!(f, " on {}", days.join(", "))?;
+        }
+        if let Some(day) = self.month_day {
+            write!(f, " on the {}{}", day, ordinal_suffix(day))?;
+        }
+        let month = self
+            .month
+            .and_then(|month| Month::try_from(month as u8).ok());
+        if let Some(month) = month {
+            let joiner = if self.month_day.is_some() { "of" } else { "in" };
+            write!(f, " {} {}", joiner, month.name())?;
+        }
+        if let Some(until) = self.until {
+            write!(f, " until {}", until.format("%Y-%m-%d"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn rule(tag: &str) -> Recurrence {
+        Recurrence::from_tag(tag).unwrap()
+    }
+
+    #[test]
+    fn tags() {
+        assert_eq!(rule("daily"), Recurrence::new(Frequency::Daily));
+        assert_eq!(rule("every-3-days").interval, 3);
+        assert_eq!(
+            rule("every-mon-thu").weekdays,
+            vec![Weekday::Mon, Weekday::Thu]
+        );
+        assert_eq!(rule("every-weekday").weekdays.len(), 5);
+        let fortnightly = rule("every-2-weeks-fri");
+        assert_eq!(
+            (fortnightly.interval, fortnightly.weekdays),
+            (2, vec![Weekday::Fri])
+        );
+        assert_eq!(rule("every-1st").month_day, Some(1));
+        assert_eq!(rule("every-3-months-15th").interval, 3);
+        let christmas = rule("every-dec-25th");
+        assert_eq!(
+            (christmas.frequency, christmas.month, christmas.month_day),
+            (Frequency::Yearly, Some(12), Some(25))
+        );
+        assert_eq!(rule("every-year-15th").month_day, Some(15));
+        assert_eq!(Recurrence::from_tag("every-2-feb"), None);
+        assert_eq!(Recurrence::from_tag("every-feb-32nd"), None);
+        assert_eq!(Recurrence::from_tag("every-foo-1st"), None);
+        assert_eq!(Recurrence::from_tag("every-2-mon"), None);
+        assert_eq!(Recurrence::from_tag("every-32nd"), None);
+        assert_eq!(Recurrence::from_tag("every-0-days"), None);
+        assert_eq!(Recurrence::from_tag("everyone"), None);
+        assert_eq!(Recurrence::from_tag("every-body"), None);
+    }
+
+    #[test]
+    fn tags_round_trip() {
+        for tag in [
+            "daily",
+            "every-3-days",
+            "weekly",
+            "every-mon",
+            "every-2-weeks-mon-wed",
+            "monthly",
+            "every-2-months",
+            "every-1st",
+            "every-3-months-22nd",
+            "yearly",
+            "every-feb-29th",
+            "every-2-years-dec-25th",
+            "every-mar",
+            "every-year-15th",
+            "every-3-years-1st",
+        ] {
+            assert_eq!(rule(tag).to_tags(), vec![tag.to_string()]);
+        }
+    }
+
+    #[test]
+    fn rrule_round_trip() {
+        let mut fortnightly = rule("every-2-weeks-mon-thu");
+        fortnightly.until = NaiveDate::from_ymd_opt(2026, 12, 31);
+        assert_eq!(
+            fortnightly.to_rrule(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;UNTIL=20261231"
+        );
+        assert_eq!(fortnightly.to_rrule().parse(), Ok(fortnightly));
+        assert_eq!(
+            "RRULE:FREQ=MONTHLY;BYMONTHDAY=1".parse(),
+            Ok(rule("every-1st"))
+        );
+        assert!("FREQ=HOURLY".parse::<Recurrence>().is_err());
+        assert!("INTERVAL=2".parse::<Recurrence>().is_err());
+        assert!("FREQ=DAILY;BYDAY=MO".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn next_weekly_occurrence() {
+        // Wednesday 14 October 2026
+        let due = at(2026, 10, 14, 9, 0);
+        let today = due.date();
+        assert_eq!(
+            rule("every-mon-wed-fri").next_due(due, today),
+            Some(at(2026, 10, 16, 9, 0))
+        );
+        assert_eq!(
+            rule("every-mon-wed").next_due(due, today),
+            Some(at(2026, 10, 19, 9, 0))
+        );
+        assert_eq!(
+            rule("every-2-weeks-mon").next_due(at(2026, 10, 12, 9, 0), today),
+            Some(at(2026, 10, 26, 9, 0))
+        );
+        assert_eq!(
+            rule("weekly").next_due(due, today),
+            Some(at(2026, 10, 21, 9, 0))
+        );
+    }
+
+    #[test]
+    fn next_monthly_and_yearly_occurrence() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let end_of_january =
+            rule("monthly").anchored_to(NaiveDate::from_ymd_opt(2026, 1, 31).unwrap());
+        let february = end_of_january
+            .next_due(at(2026, 1, 31, 9, 0), today)
+            .unwrap();
+        assert_eq!(february, at(2026, 2, 28, 9, 0));
+        assert_eq!(
+            end_of_january.next_due(february, today),
+            Some(at(2026, 3, 31, 9, 0))
+        );
+        assert_eq!(
+            rule("yearly").next_due(at(2024, 2, 29, 9, 0), today),
+            Some(at(2026, 2, 28, 9, 0))
+        );
+    }
+
+    #[test]
+    fn yearly_rules_keep_29_february() {
+        let leap_day = rule("yearly").anchored_to(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(leap_day.to_rrule(), "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29");
+        assert_eq!(leap_day.to_string(), "every year on the 29th of February");
+        assert_eq!(leap_day.to_rrule().parse(), Ok(leap_day.clone()));
+        assert_eq!(leap_day.to_tags(), ["every-feb-29th"]);
+
+        let mut due = at(2024, 2, 29, 9, 0);
+        let mut dues = Vec::new();
+        for _ in 0..4 {
+            due = leap_day.next_due(due, due.date()).unwrap();
+            dues.push(due);
+        }
+        assert_eq!(
+            dues,
+            [
+                at(2025, 2, 28, 9, 0),
+                at(2026, 2, 28, 9, 0),
+                at(2027, 2, 28, 9, 0),
+                at(2028, 2, 29, 9, 0),
+            ]
+        );
+        // Anchoring again from a shortened date keeps the original day
+        assert_eq!(leap_day.anchored_to(dues[0].date()), leap_day);
+        assert!("FREQ=MONTHLY;BYMONTH=2".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn overdue_tasks_skip_to_the_future() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        assert_eq!(
+            rule("daily").next_due(at(2026, 10, 1, 8, 0), today),
+            Some(at(2026, 10, 15, 8, 0))
+        );
+    }
+
+    #[test]
+    fn rules_end() {
+        let mut rule = rule("daily");
+        rule.until = NaiveDate::from_ymd_opt(2026, 10, 15);
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        assert_eq!(
+            rule.next_due(at(2026, 10, 14, 9, 0), today),
+            Some(at(2026, 10, 15, 9, 0))
+        );
+        assert_eq!(rule.next_due(at(2026, 10, 15, 9, 0), today), None);
+    }
+
+    #[test]
+    fn first_occurrence() {
+        // Wednesday 14 October 2026
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        assert_eq!(
+            rule("every-mon").first_due(today, nine),
+            Some(at(2026, 10, 19, 9, 0))
+        );
+        assert_eq!(
+            rule("every-wed").first_due(today, nine),
+            Some(at(2026, 10, 14, 9, 0))
+        );
+        assert_eq!(
+            rule("every-1st").first_due(today, nine),
+            Some(at(2026, 11, 1, 9, 0))
+        );
+        assert_eq!(
+            rule("daily").first_due(today, nine),
+            Some(at(2026, 10, 14, 9, 0))
+        );
+    }
+}
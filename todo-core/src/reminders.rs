@@ -0,0 +1,176 @@
+//! Reminders for tasks that are coming due.
+//!
+//! A task with a due date is reminded of at each of its reminder offsets,
+//! given in minutes before the due time: the task's own
+//! [`reminders`](crate::Task::reminders) if set, otherwise the configured
+//! ones. A snoozed reminder comes back at the task's
+//! [`snoozed_until`](crate::Task::snoozed_until). Tasks in completed columns
+//! are never reminded of.
+
+use chrono::{Duration, NaiveDateTime};
+use uuid::Uuid;
+
+use crate::model::Task;
+use crate::workflow::Workflow;
+
+/// Remind at the due time, unless configured otherwise.
+pub const DEFAULT_REMINDER_MINUTES: [u32; 1] = [0];
+/// How long a snoozed reminder waits, unless configured otherwise.
+pub const DEFAULT_SNOOZE_MINUTES: u32 = 10;
+
+/// A reminder that has come up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reminder {
+    pub task_id: Uuid,
+    pub description: String,
+    pub due_time: NaiveDateTime,
+    /// How long before the due time the reminder was set for, or `None`
+    /// when it comes back after a snooze.
+    pub minutes_before: Option<u32>,
+}
+
+impl Reminder {
+    /// A line saying when the task is due, for the notification body.
+    pub fn body(&self) -> String {
+        let due = self.due_time.format("%H:%M");
+        match self.minutes_before {
+            Some(0) => format!("Due now ({})", due),
+            Some(minutes) => format!("Due in {} at {}", format_minutes(minutes), due),
+            None => format!("Due {}", self.due_time.format("%Y-%m-%d %H:%M")),
+        }
+    }
+}
+
+/// `15 minutes`, `1 hour`, `1 hour 30 minutes`, `2 days`.
+pub fn format_minutes(minutes: u32) -> String {
+    let plural =
+        |count: u32, unit: &str| format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" });
+    if minutes >= 24 * 60 && minutes.is_multiple_of(24 * 60) {
+        return plural(minutes / (24 * 60), "day");
+    }
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => plural(minutes, "minute"),
+        (hours, 0) => plural(hours, "hour"),
+        (hours, minutes) => format!("{} {}", plural(hours, "hour"), plural(minutes, "minute")),
+    }
+}
+
+/// The reminders falling after `after` and no later than `until`, in the
+/// order of `tasks`.
+pub(crate) fn reminders_between(
+    tasks: &[Task],
+    workflow: &Workflow,
+    default_minutes: &[u32],
+    after: NaiveDateTime,
+    until: NaiveDateTime,
+) -> Vec<Reminder> {
+    let is_due = |time: NaiveDateTime| after < time && time <= until;
+    let mut reminders = Vec::new();
+    for task in tasks {
+        let Some(due_time) = task.due_time else {
+            continue;
+        };
+        if workflow.is_completed(&task.status) {
+            continue;
+        }
+        let reminder = |minutes_before| Reminder {
+            task_id: task.id,
+            description: task.description.clone(),
+            due_time,
+            minutes_before,
+        };
+        let minutes = task.reminders.as_deref().unwrap_or(default_minutes);
+        for &minutes_before in minutes {
+            if is_due(due_time - Duration::minutes(i64::from(minutes_before))) {
+                reminders.push(reminder(Some(minutes_before)));
+            }
+        }
+        if task.snoozed_until.is_some_and(is_due) {
+            reminders.push(reminder(None));
+        }
+    }
+    reminders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    use crate::testing;
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 14)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn task(status: &str, due_time: Option<NaiveDateTime>) -> Task {
+        Task {
+            status: status.to_string(),
+            due_time,
+            ..testing::task("Call the bank")
+        }
+    }
+
+    fn minutes_before(
+        tasks: &[Task],
+        after: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Vec<Option<u32>> {
+        reminders_between(tasks, &Workflow::default(), &[0, 15], after, until)
+            .into_iter()
+            .map(|reminder| reminder.minutes_before)
+            .collect()
+    }
+
+    #[test]
+    fn default_offsets() {
+        let tasks = [task("todo", Some(at(17, 0)))];
+        assert_eq!(minutes_before(&tasks, at(16, 44), at(16, 45)), [Some(15)]);
+        assert_eq!(minutes_before(&tasks, at(16, 59), at(17, 0)), [Some(0)]);
+        // Reminders already given are not repeated
+        assert!(minutes_before(&tasks, at(17, 0), at(17, 1)).is_empty());
+        assert_eq!(
+            minutes_before(&tasks, at(16, 0), at(17, 0)),
+            [Some(0), Some(15)]
+        );
+    }
+
+    #[test]
+    fn task_offsets_override_the_defaults() {
+        let mut custom = task("todo", Some(at(17, 0)));
+        custom.reminders = Some(vec![60]);
+        let mut silent = task("todo", Some(at(17, 0)));
+        silent.reminders = Some(Vec::new());
+        let tasks = [custom, silent];
+        assert_eq!(minutes_before(&tasks, at(15, 0), at(17, 0)), [Some(60)]);
+    }
+
+    #[test]
+    fn snoozed_reminders_come_back() {
+        let mut snoozed = task("todo", Some(at(17, 0)));
+        snoozed.snoozed_until = Some(at(17, 10));
+        assert_eq!(minutes_before(&[snoozed], at(17, 5), at(17, 10)), [None]);
+    }
+
+    #[test]
+    fn completed_and_undated_tasks_are_skipped() {
+        let tasks = [task("done", Some(at(17, 0))), task("todo", None)];
+        assert!(minutes_before(&tasks, at(16, 0), at(18, 0)).is_empty());
+    }
+
+    #[test]
+    fn bodies() {
+        let reminder = Reminder {
+            task_id: Uuid::nil(),
+            description: String::new(),
+            due_time: at(17, 0),
+            minutes_before: Some(90),
+        };
+        assert_eq!(reminder.body(), "Due in 1 hour 30 minutes at 17:00");
+        assert_eq!(format_minutes(2880), "2 days");
+        assert_eq!(format_minutes(1), "1 minute");
+    }
+}
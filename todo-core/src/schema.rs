@@ -0,0 +1,149 @@
+//! Versioning of the task file format.
+//!
+//! Every file written by [`save_tasks`](crate::save_tasks) is an object
+//! carrying a `schema_version` next to the task list. Older files are brought
+//! up to [`CURRENT_SCHEMA_VERSION`] by running the migrations in
+//! [`MIGRATIONS`] one after another, each taking the raw JSON of version `n`
+//! to version `n + 1`. A change to the format that cannot be expressed with
+//! `#[serde(default)]` gets a new version number and one more migration here.
+//!
+//! Version history:
+//!
+//! * 0 — a bare array of tasks without a `priority`.
+//! * 1 — a bare array of tasks with a `priority`.
+//! * 2 — `{ "schema_version": 2, "tasks": [...] }`.
+//! * 3 — adds `"columns": [...]`; a task's `status` is the id of its column
+//!   instead of one of `Todo`, `Doing` and `Done`.
+//! * 4 — a task's single `category` becomes a list of `tags`.
+
+use serde_json::{Map, Value, json};
+
+use crate::model::Priority;
+use crate::workflow::Workflow;
+
+/// The version [`save_tasks`](crate::save_tasks) writes.
+pub const CURRENT_SCHEMA_VERSION: u32 = 4;
+
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// `MIGRATIONS[n]` migrates a version `n` document to version `n + 1`.
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] = [
+    v0_add_priority,
+    v1_wrap_tasks,
+    v2_add_columns,
+    v3_category_to_tags,
+];
+
+/// Works out which version of the format `document` is in.
+pub(crate) fn detect_version(document: &Value) -> Result<u32, String> {
+    match document {
+        Value::Object(object) => match object.get("schema_version") {
+            Some(version) => version
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| format!("schema_version {} is not a version number", version)),
+            None => Err("object without a schema_version".to_string()),
+        },
+        Value::Array(tasks) => {
+            if tasks.iter().all(|task| task.get("priority").is_some()) {
+                Ok(1)
+            } else {
+                Ok(0)
+            }
+        }
+        _ => Err("expected a task list".to_string()),
+    }
+}
+
+/// Runs every migration from `version` up to [`CURRENT_SCHEMA_VERSION`].
+pub(crate) fn migrate(mut document: Value, version: u32) -> Result<Value, String> {
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        document = migration(document)
+            .map_err(|reason| format!("migrating from version {}: {}", from, reason))?;
+    }
+    Ok(document)
+}
+
+/// Version 0 → 1: tasks written before priorities existed get the same
+/// priority a newly added task would.
+fn v0_add_priority(document: Value) -> Result<Value, String> {
+    let Value::Array(tasks) = document else {
+        return Err("expected a task list".to_string());
+    };
+    let default_priority = serde_json::to_value(Priority::default()).map_err(|e| e.to_string())?;
+    tasks
+        .into_iter()
+        .map(|task| match task {
+            Value::Object(mut fields) => {
+                fields
+                    .entry("priority")
+                    .or_insert_with(|| default_priority.clone());
+                Ok(Value::Object(fields))
+            }
+            _ => Err("expected every task to be an object".to_string()),
+        })
+        .collect::<Result<Vec<Value>, String>>()
+        .map(Value::Array)
+}
+
+/// Version 1 → 2: the bare task array moves into a versioned envelope.
+fn v1_wrap_tasks(document: Value) -> Result<Value, String> {
+    if !document.is_array() {
+        return Err("expected a task list".to_string());
+    }
+    let mut envelope = Map::new();
+    envelope.insert("schema_version".to_string(), json!(2));
+    envelope.insert("tasks".to_string(), document);
+    Ok(Value::Object(envelope))
+}
+
+/// Version 2 → 3: the fixed statuses become the columns of the default
+/// workflow, whose ids are the old status names in lowercase.
+fn v2_add_columns(document: Value) -> Result<Value, String> {
+    let Value::Object(mut envelope) = document else {
+        return Err("expected an object".to_string());
+    };
+    let Some(Value::Array(tasks)) = envelope.get_mut("tasks") else {
+        return Err("expected a task list".to_string());
+    };
+    for task in tasks {
+        let Some(status) = task.get_mut("status") else {
+            return Err("expected every task to have a status".to_string());
+        };
+        let column = match status.as_str() {
+            Some("Todo") => "todo",
+            Some("Doing") => "doing",
+            Some("Done") => "done",
+            _ => return Err(format!("unknown status {}", status)),
+        };
+        *status = json!(column);
+    }
+    let columns = serde_json::to_value(Workflow::default()).map_err(|e| e.to_string())?;
+    envelope.insert("schema_version".to_string(), json!(3));
+    envelope.insert("columns".to_string(), columns);
+    Ok(Value::Object(envelope))
+}
+
+/// Version 3 → 4: a task's category becomes its only tag, lowercased like
+/// every tag.
+fn v3_category_to_tags(document: Value) -> Result<Value, String> {
+    let Value::Object(mut envelope) = document else {
+        return Err("expected an object".to_string());
+    };
+    let Some(Value::Array(tasks)) = envelope.get_mut("tasks") else {
+        return Err("expected a task list".to_string());
+    };
+    for task in tasks {
+        let Value::Object(fields) = task else {
+            return Err("expected every task to be an object".to_string());
+        };
+        let tags = match fields.remove("category") {
+            Some(Value::String(category)) => vec![json!(category.to_lowercase())],
+            Some(Value::Null) | None => Vec::new(),
+            Some(other) => return Err(format!("unexpected category {}", other)),
+        };
+        fields.insert("tags".to_string(), Value::Array(tags));
+    }
+    envelope.insert("schema_version".to_string(), json!(4));
+    Ok(Value::Object(envelope))
+}
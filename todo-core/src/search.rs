@@ -0,0 +1,173 @@
+//! Full-text search over tasks.
+//!
+//! A query is a list of terms separated by spaces. A term is a word or a
+//! `"quoted phrase"`, and matches a task whose description, tags or notes
+//! contain it, ignoring case. A term starting with `-` matches tasks that do
+//! not contain it. A task must match every term.
+
+use std::ops::Range;
+
+use crate::model::Task;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    text: String,
+    negated: bool,
+}
+
+/// A parsed search box query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    terms: Vec<Term>,
+}
+
+impl SearchQuery {
+    /// Reads a query such as `report -draft "next week"`. An unclosed quote
+    /// runs to the end of the text.
+    pub fn parse(text: &str) -> Self {
+        let mut terms = Vec::new();
+        let mut chars = text.chars().peekable();
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            let Some(&first) = chars.peek() else {
+                break;
+            };
+            let negated = first == '-';
+            if negated {
+                chars.next();
+            }
+            let mut term = String::new();
+            if chars.next_if_eq(&'"').is_some() {
+                term.extend(chars.by_ref().take_while(|&c| c != '"'));
+            } else {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    term.push(c);
+                }
+            }
+            let term = term.trim();
+            if !term.is_empty() {
+                terms.push(Term {
+                    text: term.to_lowercase(),
+                    negated,
+                });
+            }
+        }
+        SearchQuery { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        let tags: Vec<String> = task.tags.iter().map(|tag| format!("#{}", tag)).collect();
+        let fields = [&task.description, &task.notes].into_iter().chain(&tags);
+        let fields: Vec<&String> = fields.collect();
+        self.terms.iter().all(|term| {
+            let found = fields
+                .iter()
+                .any(|field| find_ignore_case(field, &term.text).next().is_some());
+            found != term.negated
+        })
+    }
+
+    /// Byte ranges of `text` that the query's (not negated) terms match, in
+    /// order and without overlaps, for highlighting.
+    pub fn highlights(&self, text: &str) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = self
+            .terms
+            .iter()
+            .filter(|term| !term.negated)
+            .flat_map(|term| find_ignore_case(text, &term.text))
+            .collect();
+        ranges.sort_by_key(|range| range.start);
+        let mut merged: Vec<Range<usize>> = Vec::new();
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+}
+
+/// Byte ranges where `needle`, which is lowercase, occurs in `haystack`,
+/// ignoring case.
+fn find_ignore_case<'a>(
+    haystack: &'a str,
+    needle: &'a str,
+) -> impl Iterator<Item = Range<usize>> + 'a {
+    haystack.char_indices().filter_map(move |(start, _)| {
+        let mut rest = haystack[start..].char_indices();
+        let mut end = start;
+        for wanted in needle.chars() {
+            let (offset, c) = rest.next()?;
+            if !c.to_lowercase().eq(wanted.to_lowercase()) {
+                return None;
+            }
+            end = start + offset + c.len_utf8();
+        }
+        Some(start..end)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing;
+
+    fn task(description: &str, tags: &[&str], notes: &str) -> Task {
+        Task {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            notes: notes.to_string(),
+            ..testing::task(description)
+        }
+    }
+
+    fn matches(query: &str, task: &Task) -> bool {
+        SearchQuery::parse(query).matches(task)
+    }
+
+    #[test]
+    fn terms_and_phrases() {
+        let query = SearchQuery::parse(r#"  report -draft "next week" -"old stuff "#);
+        let texts: Vec<(&str, bool)> = query
+            .terms
+            .iter()
+            .map(|term| (term.text.as_str(), term.negated))
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                ("report", false),
+                ("draft", true),
+                ("next week", false),
+                ("old stuff", true)
+            ]
+        );
+        assert!(SearchQuery::parse(r#" "" - "#).is_empty());
+    }
+
+    #[test]
+    fn searches_description_tags_and_notes() {
+        let task = task("Write Report", &["work"], "Send it to Ana next week");
+        assert!(matches("report", &task));
+        assert!(matches("#work", &task));
+        assert!(matches("WORK", &task));
+        assert!(matches("\"next week\"", &task));
+        assert!(!matches("\"week next\"", &task));
+        assert!(matches("report -draft", &task));
+        assert!(!matches("report -ana", &task));
+        assert!(!matches("report budget", &task));
+    }
+
+    #[test]
+    fn highlights_merge_overlapping_matches() {
+        let query = SearchQuery::parse("rep port -write");
+        assert_eq!(query.highlights("Write report, Report"), [6..12, 14..20]);
+        // Offsets are bytes, also after wider characters
+        assert_eq!(SearchQuery::parse("ä").highlights("Ärger ä"), [0..2, 7..9]);
+    }
+}
@@ -0,0 +1,184 @@
+//! Orders for the tasks within a column.
+
+use std::cmp::{Ordering, Reverse};
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Priority, Task};
+
+/// How a board orders the tasks within each column. Tasks that compare
+/// equal keep their manual order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortMode {
+    /// Highest priority first, then soonest due.
+    Priority,
+    /// Soonest due first; tasks without a due date last.
+    Due,
+    /// Newest first; tasks from before timestamps last.
+    Created,
+    /// By description, ignoring case.
+    Alphabetical,
+    /// The order tasks were added and dragged into.
+    #[default]
+    Manual,
+}
+
+impl SortMode {
+    /// Every mode, in the order they are offered.
+    pub const ALL: [SortMode; 5] = [
+        SortMode::Priority,
+        SortMode::Due,
+        SortMode::Created,
+        SortMode::Alphabetical,
+        SortMode::Manual,
+    ];
+
+    /// The mode's name in the GUI, such as "Due date".
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Priority => "Priority",
+            SortMode::Due => "Due date",
+            SortMode::Created => "Newest",
+            SortMode::Alphabetical => "A–Z",
+            SortMode::Manual => "Manual",
+        }
+    }
+
+    pub fn compare(self, a: &Task, b: &Task) -> Ordering {
+        // `None` sorts before `Some`, so comparing reversed dates the other
+        // way round puts the soonest first and tasks without one last
+        let soonest_due = |task: &Task| task.due_time.map(Reverse);
+        match self {
+            SortMode::Priority => priority_rank(&a.priority)
+                .cmp(&priority_rank(&b.priority))
+                .then_with(|| soonest_due(b).cmp(&soonest_due(a))),
+            SortMode::Due => soonest_due(b).cmp(&soonest_due(a)),
+            SortMode::Created => b.created_at.cmp(&a.created_at),
+            SortMode::Alphabetical => a
+                .description
+                .to_lowercase()
+                .cmp(&b.description.to_lowercase()),
+            SortMode::Manual => a.position.cmp(&b.position),
+        }
+    }
+
+    /// Sorts `tasks` in this order. The sort is stable, so `tasks` should be
+    /// in manual order to begin with.
+    pub fn sort(self, tasks: &mut [&Task]) {
+        tasks.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+fn priority_rank(priority: &Priority) -> u8 {
+    match priority {
+        Priority::High => 0,
+        Priority::Medium => 1,
+        Priority::Low => 2,
+    }
+}
+
+impl fmt::Display for SortMode {
+    /// The text [`SortMode::from_str`] reads back as this mode.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SortMode::Priority => "priority",
+            SortMode::Due => "due",
+            SortMode::Created => "created",
+            SortMode::Alphabetical => "alphabetical",
+            SortMode::Manual => "manual",
+        })
+    }
+}
+
+impl FromStr for SortMode {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim().to_lowercase();
+        SortMode::ALL
+            .into_iter()
+            .find(|mode| mode.to_string() == text)
+            .ok_or_else(|| {
+                format!(
+                    "invalid sort order '{}' (expected priority, due, created, alphabetical or manual)",
+                    text
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use crate::testing;
+
+    fn task(description: &str, priority: Priority, due_day: Option<u32>, position: u32) -> Task {
+        Task {
+            due_time: due_day.map(|day| {
+                NaiveDate::from_ymd_opt(2026, 10, day)
+                    .unwrap()
+                    .and_hms_opt(17, 0, 0)
+                    .unwrap()
+            }),
+            priority,
+            position,
+            created_at: Some(Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap())
+                .filter(|_| position > 0)
+                .map(|created| created + chrono::Duration::days(position.into())),
+            ..testing::task(description)
+        }
+    }
+
+    fn sorted(mode: SortMode, tasks: &[Task]) -> Vec<&str> {
+        let mut tasks: Vec<&Task> = tasks.iter().collect();
+        mode.sort(&mut tasks);
+        tasks.iter().map(|t| t.description.as_str()).collect()
+    }
+
+    #[test]
+    fn sort_modes() {
+        let tasks = [
+            task("water plants", Priority::Low, Some(20), 0),
+            task("Call Ana", Priority::High, None, 1),
+            task("budget", Priority::Medium, Some(18), 2),
+            task("Backup", Priority::High, Some(25), 3),
+            task("email", Priority::Medium, None, 4),
+        ];
+        assert_eq!(
+            sorted(SortMode::Priority, &tasks),
+            ["Backup", "Call Ana", "budget", "email", "water plants"]
+        );
+        assert_eq!(
+            sorted(SortMode::Due, &tasks),
+            ["budget", "water plants", "Backup", "Call Ana", "email"]
+        );
+        assert_eq!(
+            sorted(SortMode::Created, &tasks),
+            ["email", "Backup", "budget", "Call Ana", "water plants"]
+        );
+        assert_eq!(
+            sorted(SortMode::Alphabetical, &tasks),
+            ["Backup", "budget", "Call Ana", "email", "water plants"]
+        );
+        assert_eq!(
+            sorted(SortMode::Manual, &tasks),
+            ["water plants", "Call Ana", "budget", "Backup", "email"]
+        );
+    }
+
+    #[test]
+    fn sort_modes_round_trip() {
+        for mode in SortMode::ALL {
+            assert_eq!(mode.to_string().parse::<SortMode>(), Ok(mode));
+            let json = serde_json::to_string(&mode).unwrap();
+            assert_eq!(json, format!("\"{}\"", mode));
+        }
+        assert_eq!(" Due ".parse::<SortMode>(), Ok(SortMode::Due));
+        assert!("size".parse::<SortMode>().is_err());
+    }
+}
@@ -0,0 +1,994 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Local, NaiveDateTime, NaiveTime, Utc};
+use uuid::Uuid;
+
+use crate::dates::DEFAULT_DUE_TIME;
+use crate::error::StorageError;
+use crate::filter::TaskFilter;
+use crate::history::{Edit, History};
+use crate::model::{ChecklistItem, Task};
+use crate::parser::{ParsedTask, parse_task_description_at};
+use crate::recurrence::Recurrence;
+use crate::reminders::{self, DEFAULT_REMINDER_MINUTES, DEFAULT_SNOOZE_MINUTES, Reminder};
+use crate::sort::SortMode;
+use crate::storage::{self, Backup};
+use crate::workflow::{MoveError, Workflow, WorkflowError};
+
+/// The columns and task list backing a board, together with the file they
+/// are persisted to and the filters currently applied to them.
+///
+/// Every mutating method changes the in-memory list first and then saves it.
+/// If saving fails the change is kept and the state stays
+/// [dirty](Self::is_dirty) until a later [`save_tasks`](Self::save_tasks)
+/// succeeds, so nothing is lost while the caller reports the error.
+///
+/// Each of those changes can be [undone](Self::undo) and
+/// [redone](Self::redo) until another board or file is loaded.
+pub struct AppState {
+    /// Change through [`set_workflow`](Self::set_workflow) so it can be
+    /// undone.
+    pub workflow: Workflow,
+    /// How [`filtered_tasks`](Self::filtered_tasks) orders each column.
+    /// Change through [`set_sort`](Self::set_sort) so it is saved.
+    pub sort: SortMode,
+    /// Change through the methods below; history refers to tasks by index.
+    pub tasks: Vec<Task>,
+    pub file_path: PathBuf,
+    pub filter: TaskFilter,
+    /// Number of timestamped backups kept next to `file_path`.
+    pub backup_count: usize,
+    /// Time of day for due dates added without one.
+    pub default_due_time: NaiveTime,
+    /// Minutes before the due time to remind at, for tasks without their
+    /// own reminders.
+    pub reminder_minutes: Vec<u32>,
+    /// How long [`snooze_reminder`](Self::snooze_reminder) puts a reminder
+    /// off for.
+    pub snooze_minutes: u32,
+    dirty: bool,
+    /// Set while `file_path` could not be loaded. Saving is refused
+    /// meanwhile, so the board shown in its place never replaces the file.
+    load_failed: bool,
+    /// Where the file that could not be loaded was copied to.
+    unreadable_copy: Option<PathBuf>,
+    history: History,
+    /// Edits made by the mutation in progress, recorded by `commit`.
+    pending: Vec<Edit>,
+}
+
+impl AppState {
+    /// Creates an empty state bound to `file_path`; call
+    /// [`load_tasks`](Self::load_tasks) to read its contents.
+    pub fn new(file_path: PathBuf) -> Self {
+        AppState {
+            workflow: Workflow::default(),
+            sort: SortMode::default(),
+            tasks: Vec::new(),
+            file_path,
+            filter: TaskFilter::default(),
+            backup_count: storage::DEFAULT_BACKUP_COUNT,
+            default_due_time: DEFAULT_DUE_TIME,
+            reminder_minutes: DEFAULT_REMINDER_MINUTES.to_vec(),
+            snooze_minutes: DEFAULT_SNOOZE_MINUTES,
+            dirty: false,
+            load_failed: false,
+            unreadable_copy: None,
+            history: History::default(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Reads the tasks from `file_path`, replacing those in memory. If the
+    /// file cannot be read, a copy of it is [set aside](Self::unreadable_copy)
+    /// and saving is refused until a later load succeeds.
+    pub fn load_tasks(&mut self) -> Result<(), StorageError> {
+        let data = match storage::load_tasks(&self.file_path) {
+            Ok(data) => data,
+            Err(e) => {
+                self.load_failed = true;
+                if self.file_path.exists() {
+                    self.unreadable_copy = storage::set_aside_unreadable(&self.file_path).ok();
+                }
+                return Err(e);
+            }
+        };
+        self.load_failed = false;
+        self.unreadable_copy = None;
+        self.workflow = data.workflow;
+        self.sort = data.sort;
+        self.tasks = data.tasks;
+        self.sort_by_position();
+        self.history.clear();
+        self.dirty = false;
+        Ok(())
+    }
+
+    pub fn save_tasks(&mut self) -> Result<(), StorageError> {
+        self.dirty = true;
+        if self.load_failed {
+            return Err(StorageError::NotLoaded {
+                path: self.file_path.clone(),
+            });
+        }
+        storage::save_tasks(
+            &self.file_path,
+            &self.workflow,
+            self.sort,
+            &self.tasks,
+            self.backup_count,
+        )?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Saves the tasks to `path` and makes it the file used from now on.
+    /// On failure the state keeps its previous `file_path`.
+    pub fn save_as(&mut self, path: PathBuf) -> Result<(), StorageError> {
+        let previous = std::mem::replace(&mut self.file_path, path);
+        let load_failed = std::mem::replace(&mut self.load_failed, false);
+        self.save_tasks().inspect_err(|_| {
+            self.file_path = previous;
+            self.load_failed = load_failed;
+        })
+    }
+
+    /// Whether the last [`load_tasks`](Self::load_tasks) failed, so saving
+    /// is refused.
+    pub fn load_failed(&self) -> bool {
+        self.load_failed
+    }
+
+    /// Where a copy of the file the last load failed on was put, if it
+    /// could be copied.
+    pub fn unreadable_copy(&self) -> Option<&Path> {
+        self.unreadable_copy.as_deref()
+    }
+
+    /// Whether there are changes that have not been written to `file_path`.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Backups of `file_path`, newest first.
+    pub fn list_backups(&self) -> Result<Vec<Backup>, StorageError> {
+        storage::list_backups(&self.file_path)
+    }
+
+    /// Replaces the columns, sort mode and tasks with the contents of
+    /// `backup` and saves them. The restore can be undone like any other
+    /// change, except for the sort mode, which is never part of the history.
+    /// The file being replaced is backed up as well.
+    pub fn restore_backup(&mut self, backup: &Path) -> Result<(), StorageError> {
+        let data = storage::read_tasks(backup)?;
+        let mut tasks = data.tasks;
+        tasks.sort_by_key(|t| t.position);
+        self.sort = data.sort;
+
+        self.edit(Edit::SetWorkflow {
+            before: self.workflow.clone(),
+            after: data.workflow,
+        });
+        for index in (0..self.tasks.len()).rev() {
+            self.remove_task_at(index);
+        }
+        for (index, task) in tasks.into_iter().enumerate() {
+            self.insert_task(index, task);
+        }
+        self.commit("Restore backup")
+    }
+
+    /// Reverts the most recent change and saves. Returns what was undone,
+    /// e.g. "Delete task", or `None` if there was nothing to undo.
+    pub fn undo(&mut self) -> Result<Option<String>, StorageError> {
+        let label = self.history.undo(&mut self.tasks, &mut self.workflow);
+        if label.is_some() {
+            self.save_tasks()?;
+        }
+        Ok(label)
+    }
+
+    /// Makes the most recently undone change again and saves. Returns what
+    /// was redone, or `None` if there was nothing to redo.
+    pub fn redo(&mut self) -> Result<Option<String>, StorageError> {
+        let label = self.history.redo(&mut self.tasks, &mut self.workflow);
+        if label.is_some() {
+            self.save_tasks()?;
+        }
+        Ok(label)
+    }
+
+    /// What [`undo`](Self::undo) would revert, if anything.
+    pub fn undo_label(&self) -> Option<&str> {
+        self.history.undo_label()
+    }
+
+    /// What [`redo`](Self::redo) would make again, if anything.
+    pub fn redo_label(&self) -> Option<&str> {
+        self.history.redo_label()
+    }
+
+    /// Parses `full_description` with [`parse_task_description`](crate::parse_task_description) and appends
+    /// the resulting task to the first column. Returns the new task's id.
+    pub fn add_task(&mut self, full_description: String) -> Result<Uuid, StorageError> {
+        let parsed = self.parse(&full_description);
+        let now = Utc::now();
+        let new_task = Task {
+            id: Uuid::new_v4(),
+            description: parsed.description,
+            status: self.workflow.first().id.clone(),
+            tags: parsed.tags,
+            due_time: parsed.due_time,
+            priority: parsed.priority.unwrap_or_default(),
+            position: self.next_position(&self.workflow.first().id),
+            checklist: parsed
+                .checklist
+                .iter()
+                .map(|item| ChecklistItem::new(item))
+                .collect(),
+            notes: String::new(),
+            recurrence: parsed.recurrence,
+            reminders: None,
+            snoozed_until: None,
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        let id = new_task.id;
+        self.insert_task(self.tasks.len(), new_task);
+        self.commit("Add task")?;
+        Ok(id)
+    }
+
+    /// Moves a task to the bottom of the column with id `new_status`,
+    /// respecting a strict WIP limit. Completing a recurring task adds its
+    /// next occurrence to the first column; see [`move_task`](Self::move_task).
+    pub fn update_task_status(&mut self, id: Uuid, new_status: &str) -> Result<(), MoveError> {
+        self.move_task(id, new_status, None, false)
+    }
+
+    /// Moves a task into the column with id `status`, just above the task
+    /// `before`, or to the bottom of the column when `before` is `None` or
+    /// not in that column. Positions in the column are renumbered to match.
+    /// Unknown tasks and columns are ignored.
+    ///
+    /// Moving into a full column with a strict WIP limit fails with
+    /// [`MoveError::WipLimit`] unless `force` is set.
+    ///
+    /// When a recurring task moves into a completed column, a copy due on
+    /// the rule's next day is added to the bottom of the first column with
+    /// its checklist unticked, and the rule moves to that copy. Undoing the
+    /// move removes the copy as well.
+    pub fn move_task(
+        &mut self,
+        id: Uuid,
+        status: &str,
+        before: Option<Uuid>,
+        force: bool,
+    ) -> Result<(), MoveError> {
+        let Some(column) = self.workflow.column(status) else {
+            return Ok(());
+        };
+        let Some(from) = self.tasks.iter().position(|t| t.id == id) else {
+            return Ok(());
+        };
+        if before == Some(id) {
+            return Ok(());
+        }
+        if let Some(limit) = column.wip_limit
+            && column.strict_limit
+            && !force
+            && self.tasks[from].status != status
+            && self.count_in_column(status) >= limit
+        {
+            return Err(MoveError::WipLimit {
+                column: column.name.clone(),
+                limit,
+            });
+        }
+
+        let original = self.remove_task_at(from);
+        let mut task = original.clone();
+        let completes =
+            self.workflow.is_completed(status) && !self.workflow.is_completed(&task.status);
+        let next_occurrence = if completes {
+            task.recurrence
+                .take()
+                .and_then(|rule| self.next_occurrence(&task, rule))
+        } else {
+            None
+        };
+        if task.status != status {
+            task.status = status.to_string();
+            task.updated_at = Some(Utc::now());
+        }
+
+        // `tasks` is kept in position order, so inserting next to `before`
+        // (or after the column's last task) puts it in the right place.
+        let index = before
+            .and_then(|before| {
+                self.tasks
+                    .iter()
+                    .position(|t| t.id == before && t.status == task.status)
+            })
+            .or_else(|| {
+                self.tasks
+                    .iter()
+                    .rposition(|t| t.status == task.status)
+                    .map(|i| i + 1)
+            })
+            .unwrap_or(self.tasks.len());
+        let unmoved = index == from && task == original;
+        self.insert_task(index, task);
+        if unmoved {
+            // Dropped back where it was; nothing to undo
+            self.pending.clear();
+        }
+
+        let status = status.to_string();
+        let mut position = 0;
+        for index in 0..self.tasks.len() {
+            if self.tasks[index].status == status {
+                let mut task = self.tasks[index].clone();
+                task.position = position;
+                self.replace_task(index, task);
+                position += 1;
+            }
+        }
+        if let Some(next) = next_occurrence {
+            let index = self
+                .tasks
+                .iter()
+                .rposition(|t| t.status == next.status)
+                .map_or(self.tasks.len(), |i| i + 1);
+            self.insert_task(index, next);
+        }
+        self.commit("Move task")?;
+        Ok(())
+    }
+
+    /// The task that follows `task` under `rule`, ready for the first
+    /// column, or `None` once the rule has ended.
+    fn next_occurrence(&self, task: &Task, rule: Recurrence) -> Option<Task> {
+        let now = Local::now().naive_local();
+        let due = task.due_time.unwrap_or(now);
+        let rule = rule.anchored_to(due.date());
+        let due_time = rule.next_due(due, now.date())?;
+        let first = self.workflow.first().id.clone();
+        let created = Utc::now();
+        Some(Task {
+            id: Uuid::new_v4(),
+            position: self.next_position(&first),
+            status: first,
+            due_time: Some(due_time),
+            checklist: task
+                .checklist
+                .iter()
+                .map(|item| ChecklistItem {
+                    done: false,
+                    ..item.clone()
+                })
+                .collect(),
+            recurrence: Some(rule),
+            snoozed_until: None,
+            created_at: Some(created),
+            updated_at: Some(created),
+            ..task.clone()
+        })
+    }
+
+    pub fn update_task_description(
+        &mut self,
+        id: Uuid,
+        new_description: String,
+    ) -> Result<(), StorageError> {
+        self.modify_task(id, "Edit task", |task| task.description = new_description)
+    }
+
+    /// Replaces description, tags, due time, repeat rule and priority of a
+    /// task with the result of re-parsing an edited quick-add line, so a line
+    /// without a priority makes it Medium again, as formatting it leaves
+    /// Medium out. Checklist items in the line are added after the existing
+    /// ones.
+    pub fn update_task_from_text(
+        &mut self,
+        id: Uuid,
+        full_description: &str,
+    ) -> Result<(), StorageError> {
+        let parsed = self.parse(full_description);
+        self.modify_task(id, "Edit task", |task| {
+            task.description = parsed.description;
+            task.tags = parsed.tags;
+            task.due_time = parsed.due_time;
+            task.recurrence = parsed.recurrence;
+            task.priority = parsed.priority.unwrap_or_default();
+            task.checklist
+                .extend(parsed.checklist.iter().map(|item| ChecklistItem::new(item)));
+        })
+    }
+
+    /// Replaces the task with the same id as `edited` by it, as the detail
+    /// pane does. Its column and position are kept; use
+    /// [`move_task`](Self::move_task) to change those.
+    pub fn update_task(&mut self, edited: Task) -> Result<(), StorageError> {
+        self.modify_task(edited.id, "Edit task", |task| {
+            *task = Task {
+                status: task.status.clone(),
+                position: task.position,
+                created_at: task.created_at,
+                ..edited
+            };
+        })
+    }
+
+    pub fn set_task_notes(&mut self, id: Uuid, notes: &str) -> Result<(), StorageError> {
+        self.modify_task(id, "Edit notes", |task| task.notes = notes.to_string())
+    }
+
+    /// Sets the minutes before its due time a task is reminded at, or
+    /// `None` to use [`reminder_minutes`](Self::reminder_minutes).
+    pub fn set_task_reminders(
+        &mut self,
+        id: Uuid,
+        reminders: Option<Vec<u32>>,
+    ) -> Result<(), StorageError> {
+        self.modify_task(id, "Change reminders", |task| task.reminders = reminders)
+    }
+
+    /// Brings a task's reminder back [`snooze_minutes`](Self::snooze_minutes)
+    /// after `now`.
+    pub fn snooze_reminder(&mut self, id: Uuid, now: NaiveDateTime) -> Result<(), StorageError> {
+        let until = now + Duration::minutes(i64::from(self.snooze_minutes));
+        self.modify_task(id, "Snooze reminder", |task| {
+            task.snoozed_until = Some(until)
+        })
+    }
+
+    /// Reminders that came up after `after` and no later than `until`;
+    /// see [`Reminder`].
+    pub fn reminders_between(&self, after: NaiveDateTime, until: NaiveDateTime) -> Vec<Reminder> {
+        reminders::reminders_between(
+            &self.tasks,
+            &self.workflow,
+            &self.reminder_minutes,
+            after,
+            until,
+        )
+    }
+
+    /// Appends an item to a task's checklist. Blank items are ignored.
+    pub fn add_checklist_item(&mut self, id: Uuid, text: &str) -> Result<(), StorageError> {
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+        self.modify_task(id, "Add checklist item", |task| {
+            task.checklist.push(ChecklistItem::new(text));
+        })
+    }
+
+    /// Ticks or unticks the checklist item at `index`.
+    pub fn set_checklist_item_done(
+        &mut self,
+        id: Uuid,
+        index: usize,
+        done: bool,
+    ) -> Result<(), StorageError> {
+        let label = if done {
+            "Tick checklist item"
+        } else {
+            "Untick checklist item"
+        };
+        self.modify_task(id, label, |task| {
+            if let Some(item) = task.checklist.get_mut(index) {
+                item.done = done;
+            }
+        })
+    }
+
+    pub fn remove_checklist_item(&mut self, id: Uuid, index: usize) -> Result<(), StorageError> {
+        self.modify_task(id, "Remove checklist item", |task| {
+            if index < task.checklist.len() {
+                task.checklist.remove(index);
+            }
+        })
+    }
+
+    /// Applies `change` to a copy of the task `id` and records the result as
+    /// the undoable change `label`, updating the task's `updated_at` if
+    /// anything changed. Unknown tasks are ignored.
+    fn modify_task(
+        &mut self,
+        id: Uuid,
+        label: &str,
+        change: impl FnOnce(&mut Task),
+    ) -> Result<(), StorageError> {
+        if let Some(index) = self.index_of(id) {
+            let mut task = self.tasks[index].clone();
+            change(&mut task);
+            if task != self.tasks[index] {
+                task.updated_at = Some(Utc::now());
+                self.replace_task(index, task);
+            }
+        }
+        self.commit(label)
+    }
+
+    pub fn delete_task(&mut self, id: Uuid) -> Result<(), StorageError> {
+        if let Some(index) = self.index_of(id) {
+            self.remove_task_at(index);
+        }
+        self.commit("Delete task")
+    }
+
+    /// [`parse_task_description`](crate::parse_task_description) with the
+    /// configured default due time.
+    fn parse(&self, full_description: &str) -> ParsedTask {
+        parse_task_description_at(
+            full_description,
+            Local::now().naive_local(),
+            self.default_due_time,
+        )
+    }
+
+    fn index_of(&self, id: Uuid) -> Option<usize> {
+        self.tasks.iter().position(|t| t.id == id)
+    }
+
+    /// Applies `edit` and adds it to the change being made.
+    fn edit(&mut self, edit: Edit) {
+        edit.apply(&mut self.tasks, &mut self.workflow);
+        self.pending.push(edit);
+    }
+
+    fn insert_task(&mut self, index: usize, task: Task) {
+        self.edit(Edit::Insert { index, task });
+    }
+
+    fn remove_task_at(&mut self, index: usize) -> Task {
+        let task = self.tasks[index].clone();
+        self.edit(Edit::Remove {
+            index,
+            task: task.clone(),
+        });
+        task
+    }
+
+    fn replace_task(&mut self, index: usize, task: Task) {
+        if self.tasks[index] != task {
+            self.edit(Edit::Replace {
+                index,
+                before: Box::new(self.tasks[index].clone()),
+                after: Box::new(task),
+            });
+        }
+    }
+
+    /// Records the edits made since the last commit as one undoable change
+    /// called `label`, then saves. Does nothing if nothing changed.
+    fn commit(&mut self, label: &str) -> Result<(), StorageError> {
+        let edits = std::mem::take(&mut self.pending);
+        if edits.is_empty() {
+            return Ok(());
+        }
+        self.history.record(label, edits);
+        self.save_tasks()
+    }
+
+    /// Puts `tasks` in position order. The sort is stable, so tasks from
+    /// files without positions keep their stored order.
+    fn sort_by_position(&mut self) {
+        self.tasks.sort_by_key(|t| t.position);
+    }
+
+    /// The position that puts a task at the bottom of the `status` column.
+    fn next_position(&self, status: &str) -> u32 {
+        self.tasks
+            .iter()
+            .filter(|t| t.status == status)
+            .map(|t| t.position + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Replaces the board's columns after checking them with
+    /// [`Workflow::validate`]. Tasks in a column that no longer exists are
+    /// moved to the bottom of the first column.
+    pub fn set_workflow(&mut self, workflow: Workflow) -> Result<(), WorkflowError> {
+        workflow.validate()?;
+        if workflow == self.workflow {
+            return Ok(());
+        }
+        self.edit(Edit::SetWorkflow {
+            before: self.workflow.clone(),
+            after: workflow,
+        });
+
+        let orphans: Vec<usize> = (0..self.tasks.len())
+            .filter(|&i| self.workflow.column(&self.tasks[i].status).is_none())
+            .collect();
+        let mut moved: Vec<Task> = orphans
+            .into_iter()
+            .rev()
+            .map(|index| self.remove_task_at(index))
+            .collect();
+        moved.reverse();
+
+        let first = self.workflow.first().id.clone();
+        let position = self.next_position(&first);
+        for (offset, mut task) in (0..).zip(moved) {
+            task.status = first.clone();
+            task.position = position + offset;
+            self.insert_task(self.tasks.len(), task);
+        }
+        self.commit("Change columns")?;
+        Ok(())
+    }
+
+    /// Number of tasks in the column with id `status`, ignoring the filter.
+    pub fn count_in_column(&self, status: &str) -> usize {
+        self.tasks.iter().filter(|t| t.status == status).count()
+    }
+
+    pub fn find_task(&self, id: Uuid) -> Option<&Task> {
+        self.tasks.iter().find(|t| t.id == id)
+    }
+
+    /// Tasks that pass the current [`filter`](Self::filter), column by
+    /// column in workflow order and in the board's [`sort`](Self::sort)
+    /// order within each column.
+    pub fn filtered_tasks(&self) -> impl Iterator<Item = &Task> {
+        let now = Local::now().naive_local();
+        let mut tasks: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|task| self.filter.matches(task, &self.workflow, now))
+            .collect();
+        let column_index = |task: &Task| {
+            self.workflow
+                .columns
+                .iter()
+                .position(|column| column.id == task.status)
+        };
+        tasks.sort_by(|a, b| {
+            column_index(a)
+                .cmp(&column_index(b))
+                .then_with(|| self.sort.compare(a, b))
+        });
+        tasks.into_iter()
+    }
+
+    /// Changes how the board is sorted and saves it. Not recorded for undo,
+    /// as it leaves the tasks themselves alone.
+    pub fn set_sort(&mut self, sort: SortMode) -> Result<(), StorageError> {
+        if sort == self.sort {
+            return Ok(());
+        }
+        self.sort = sort;
+        self.save_tasks()
+    }
+
+    /// Every tag used on the board, sorted.
+    pub fn all_tags(&self) -> Vec<String> {
+        let tags: HashSet<&String> = self.tasks.iter().flat_map(|task| &task.tags).collect();
+        let mut sorted_tags: Vec<String> = tags.into_iter().cloned().collect();
+        sorted_tags.sort();
+        sorted_tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Priority;
+    use crate::parser::format_task_description;
+    use crate::testing::TempDir;
+
+    /// A state saving to a fresh directory, which must outlive it.
+    fn state_in(dir: &TempDir) -> AppState {
+        AppState::new(dir.path().join("tasks.json"))
+    }
+
+    /// Adds a task per line to the first column, returning their ids.
+    fn add(state: &mut AppState, lines: &[&str]) -> Vec<Uuid> {
+        lines
+            .iter()
+            .map(|line| state.add_task(line.to_string()).unwrap())
+            .collect()
+    }
+
+    fn descriptions<'a>(tasks: impl Iterator<Item = &'a Task>) -> Vec<&'a str> {
+        tasks.map(|task| task.description.as_str()).collect()
+    }
+
+    /// The tasks in column `status` as `(description, position)`, in the
+    /// order they are stored.
+    fn column<'a>(state: &'a AppState, status: &str) -> Vec<(&'a str, u32)> {
+        state
+            .tasks
+            .iter()
+            .filter(|task| task.status == status)
+            .map(|task| (task.description.as_str(), task.position))
+            .collect()
+    }
+
+    fn workflow_with(edit: impl FnOnce(&mut Workflow)) -> Workflow {
+        let mut workflow = Workflow::default();
+        edit(&mut workflow);
+        workflow
+    }
+
+    #[test]
+    fn unreadable_file_is_set_aside_and_never_saved_over() {
+        let dir = TempDir::new();
+        let path = dir.path().join("tasks.json");
+        std::fs::write(&path, "{ not json").unwrap();
+
+        let mut state = AppState::new(path.clone());
+        assert!(state.load_tasks().is_err());
+        assert!(state.load_failed());
+        let copy = state.unreadable_copy().unwrap().to_path_buf();
+        assert_eq!(std::fs::read_to_string(&copy).unwrap(), "{ not json");
+
+        // Edits stay in memory; the file is not replaced
+        assert!(matches!(
+            state.add_task("Buy milk".to_string()),
+            Err(StorageError::NotLoaded { .. })
+        ));
+        assert!(state.is_dirty());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{ not json");
+
+        // Failing again keeps the one copy
+        assert!(state.load_tasks().is_err());
+        assert_eq!(state.unreadable_copy(), Some(copy.as_path()));
+        assert_eq!(dir.file_names().len(), 2);
+
+        // Once the file is fixed, a retry loads it and saving resumes
+        std::fs::remove_file(&path).unwrap();
+        state.load_tasks().unwrap();
+        assert!(!state.load_failed());
+        assert!(!state.is_dirty());
+        state.add_task("Buy milk".to_string()).unwrap();
+        assert_eq!(storage::load_tasks(&path).unwrap().tasks.len(), 1);
+    }
+
+    #[test]
+    fn sorts_within_each_column() {
+        let dir = TempDir::new();
+        let mut state = state_in(&dir);
+        let ids = add(&mut state, &["Write report #p1", "Tidy desk #p3"]);
+        add(&mut state, &["Call Ana", "Pay rent #p1"]);
+        state.update_task_status(ids[1], "doing").unwrap();
+        state.update_task_status(ids[0], "doing").unwrap();
+
+        state.set_sort(SortMode::Priority).unwrap();
+        assert_eq!(
+            descriptions(state.filtered_tasks()),
+            ["Pay rent", "Call Ana", "Write report", "Tidy desk"]
+        );
+        assert_eq!(state.find_task(ids[1]).unwrap().priority, Priority::Low);
+
+        // Positions only order tasks within their column
+        state.set_sort(SortMode::Manual).unwrap();
+        assert_eq!(
+            descriptions(state.filtered_tasks()),
+            ["Call Ana", "Pay rent", "Tidy desk", "Write report"]
+        );
+    }
+
+    #[test]
+    fn moving_renumbers_the_column_dropped_into() {
+        let dir = TempDir::new();
+        let mut state = state_in(&dir);
+        let [a, b, c, d] = add(&mut state, &["A", "B", "C", "D"])[..] else {
+            unreachable!()
+        };
+
+        // Up and down within a column
+        state.move_task(d, "todo", Some(b), false).unwrap();
+        assert_eq!(
+            column(&state, "todo"),
+            [("A", 0), ("D", 1), ("B", 2), ("C", 3)]
+        );
+        state.move_task(a, "todo", None, false).unwrap();
+        assert_eq!(
+            column(&state, "todo"),
+            [("D", 0), ("B", 1), ("C", 2), ("A", 3)]
+        );
+        state.move_task(d, "todo", Some(a), false).unwrap();
+        assert_eq!(
+            column(&state, "todo"),
+            [("B", 0), ("C", 1), ("D", 2), ("A", 3)]
+        );
+
+        // Across columns, to the bottom and then above a task
+        state.move_task(b, "doing", None, false).unwrap();
+        state.move_task(c, "doing", Some(b), false).unwrap();
+        assert_eq!(column(&state, "doing"), [("C", 0), ("B", 1)]);
+        // The column left keeps its order
+        assert_eq!(column(&state, "todo"), [("D", 2), ("A", 3)]);
+
+        // Dropping a task where it already is records nothing to undo
+        let label = state.undo_label().map(str::to_string);
+        state.move_task(a, "todo", None, false).unwrap();
+        state.move_task(c, "doing", Some(c), false).unwrap();
+        assert_eq!(state.undo_label().map(str::to_string), label);
+
+        // Saved positions read back in the same order
+        let mut reloaded = state_in(&dir);
+        reloaded.load_tasks().unwrap();
+        assert_eq!(column(&reloaded, "doing"), column(&state, "doing"));
+        assert_eq!(column(&reloaded, "todo"), column(&state, "todo"));
+    }
+
+    #[test]
+    fn removing_a_column_moves_its_tasks_to_the_first() {
+        let dir = TempDir::new();
+        let mut state = state_in(&dir);
+        let ids = add(&mut state, &["A", "B", "C", "D"]);
+        state.move_task(ids[3], "doing", None, false).unwrap();
+        state.move_task(ids[2], "doing", None, false).unwrap();
+
+        let invalid = workflow_with(|workflow| workflow.columns.clear());
+        assert!(state.set_workflow(invalid).is_err());
+        assert_eq!(state.workflow, Workflow::default());
+
+        let without_doing = workflow_with(|workflow| {
+            workflow.columns.remove(1);
+        });
+        state.set_workflow(without_doing.clone()).unwrap();
+        assert_eq!(state.workflow, without_doing);
+        assert_eq!(
+            column(&state, "todo"),
+            [("A", 0), ("B", 1), ("D", 2), ("C", 3)]
+        );
+        assert_eq!(state.count_in_column("doing"), 0);
+        let mut reloaded = state_in(&dir);
+        reloaded.load_tasks().unwrap();
+        assert_eq!(reloaded.workflow, without_doing);
+        assert_eq!(column(&reloaded, "todo"), column(&state, "todo"));
+    }
+
+    #[test]
+    fn strict_wip_limits_refuse_moves_unless_forced() {
+        let dir = TempDir::new();
+        let mut state = state_in(&dir);
+        let ids = add(&mut state, &["A", "B", "C"]);
+        state
+            .set_workflow(workflow_with(|workflow| {
+                workflow.columns[1].wip_limit = Some(1);
+                workflow.columns[1].strict_limit = true;
+            }))
+            .unwrap();
+
+        state.move_task(ids[0], "doing", None, false).unwrap();
+        let refused = state.move_task(ids[1], "doing", None, false);
+        assert!(matches!(
+            refused,
+            Err(MoveError::WipLimit { ref column, limit: 1 }) if column == "Doing"
+        ));
+        assert_eq!(state.find_task(ids[1]).unwrap().status, "todo");
+        assert_eq!(state.undo_label(), Some("Move task"));
+
+        state.move_task(ids[1], "doing", None, true).unwrap();
+        assert_eq!(column(&state, "doing"), [("A", 0), ("B", 1)]);
+        // Reordering within a full column is not a move into it
+        state
+            .move_task(ids[1], "doing", Some(ids[0]), false)
+            .unwrap();
+        assert_eq!(column(&state, "doing"), [("B", 0), ("A", 1)]);
+
+        // A limit that is not strict only warns
+        state
+            .set_workflow(workflow_with(|workflow| {
+                workflow.columns[1].wip_limit = Some(1);
+            }))
+            .unwrap();
+        state.move_task(ids[2], "doing", None, false).unwrap();
+        assert_eq!(state.count_in_column("doing"), 3);
+    }
+
+    /// Checks that undoing the change `change` makes to `state` restores
+    /// it, in memory and on disk, and that redoing makes it again.
+    fn assert_round_trip(state: &mut AppState, change: impl FnOnce(&mut AppState)) {
+        let before = (state.workflow.clone(), state.tasks.clone());
+        change(state);
+        let after = (state.workflow.clone(), state.tasks.clone());
+        assert_ne!(before, after);
+
+        let saved = |state: &AppState| {
+            let data = storage::load_tasks(&state.file_path).unwrap();
+            (data.workflow, data.tasks)
+        };
+        state.undo().unwrap().unwrap();
+        assert_eq!((state.workflow.clone(), state.tasks.clone()), before);
+        assert_eq!(saved(state), before);
+        state.redo().unwrap().unwrap();
+        assert_eq!((state.workflow.clone(), state.tasks.clone()), after);
+        assert_eq!(saved(state), after);
+    }
+
+    #[test]
+    fn undo_and_redo_multi_edit_changes() {
+        let dir = TempDir::new();
+        let mut state = state_in(&dir);
+        let ids = add(&mut state, &["A", "B", "C", "D"]);
+        state.move_task(ids[1], "doing", None, false).unwrap();
+
+        assert_round_trip(&mut state, |state| {
+            state
+                .move_task(ids[3], "todo", Some(ids[0]), false)
+                .unwrap()
+        });
+        assert_round_trip(&mut state, |state| {
+            state
+                .move_task(ids[2], "doing", Some(ids[1]), false)
+                .unwrap()
+        });
+        assert_round_trip(&mut state, |state| {
+            let without_doing = workflow_with(|workflow| {
+                workflow.columns.remove(1);
+            });
+            state.set_workflow(without_doing).unwrap()
+        });
+    }
+
+    #[test]
+    fn restoring_a_backup_can_be_undone() {
+        let dir = TempDir::new();
+        let mut state = state_in(&dir);
+        add(&mut state, &["A", "B"]);
+        state.set_sort(SortMode::Due).unwrap();
+        // Backups are named to the millisecond
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let ids = add(&mut state, &["C"]);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        state
+            .set_workflow(workflow_with(|workflow| {
+                workflow.columns[0].name = "Backlog".to_string();
+            }))
+            .unwrap();
+        state.move_task(ids[0], "done", None, false).unwrap();
+        state.set_sort(SortMode::Alphabetical).unwrap();
+
+        // The backup made when C was added
+        let backup = state
+            .list_backups()
+            .unwrap()
+            .into_iter()
+            .find(|backup| {
+                let data = storage::read_tasks(&backup.path).unwrap();
+                data.tasks.len() == 2 && data.sort == SortMode::Due
+            })
+            .unwrap();
+        assert_round_trip(&mut state, |state| {
+            state.restore_backup(&backup.path).unwrap()
+        });
+        assert_eq!(descriptions(state.tasks.iter()), ["A", "B"]);
+        assert_eq!(state.workflow, Workflow::default());
+        assert_eq!(state.sort, SortMode::Due);
+        let saved = storage::load_tasks(&state.file_path).unwrap();
+        assert_eq!(saved.sort, SortMode::Due);
+    }
+
+    #[test]
+    fn editing_the_line_replaces_every_field_it_describes() {
+        let dir = TempDir::new();
+        let mut state = state_in(&dir);
+        let ids = add(&mut state, &["Report #p1 #work @2026-10-20"]);
+        let line = format_task_description(state.find_task(ids[0]).unwrap());
+
+        // Removing the priority and the tag from the line drops both
+        let edited = line.replace(" #p1", "").replace(" #work", "");
+        state.update_task_from_text(ids[0], &edited).unwrap();
+        let task = state.find_task(ids[0]).unwrap();
+        assert_eq!(task.priority, Priority::Medium);
+        assert!(task.tags.is_empty());
+        assert!(task.due_time.is_some());
+        assert_eq!(format_task_description(task), edited);
+
+        state.update_task_from_text(ids[0], "Report #p3").unwrap();
+        let task = state.find_task(ids[0]).unwrap();
+        assert_eq!(task.priority, Priority::Low);
+        assert_eq!(task.due_time, None);
+    }
+}
@@ -0,0 +1,580 @@
+use std::cmp::Reverse;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::StorageError;
+use crate::model::Task;
+use crate::schema::{self, CURRENT_SCHEMA_VERSION};
+use crate::sort::SortMode;
+use crate::workflow::Workflow;
+
+/// On-disk layout of a task file at [`CURRENT_SCHEMA_VERSION`].
+#[derive(Serialize, Deserialize)]
+struct TaskFile<W, T> {
+    schema_version: u32,
+    columns: W,
+    /// Missing from files written before sorting.
+    #[serde(default)]
+    sort: SortMode,
+    tasks: T,
+}
+
+/// The contents of a task file: a board's columns, how it sorts them and
+/// its tasks.
+#[derive(Debug, Clone, Default)]
+pub struct BoardData {
+    pub workflow: Workflow,
+    pub sort: SortMode,
+    pub tasks: Vec<Task>,
+}
+
+/// Reads the board stored at `path`. A missing file is an empty board with
+/// the default columns.
+///
+/// Files written in an older format are migrated in memory; the original is
+/// first copied to `<file>.schema-v<N>.bak` so the migration can be undone by
+/// hand. The live file itself is only rewritten by the next save.
+pub fn load_tasks(path: &Path) -> Result<BoardData, StorageError> {
+    let Some((document, version)) = read_document(path)? else {
+        return Ok(BoardData::default());
+    };
+    let data = decode_document(path, document, version)?;
+    if version < CURRENT_SCHEMA_VERSION {
+        let backup_path = sibling_path(path, |name| {
+            let mut backup = name.to_os_string();
+            backup.push(format!(".schema-v{}.{}", version, BACKUP_EXTENSION));
+            backup
+        });
+        if !backup_path.exists() {
+            fs::copy(path, &backup_path).map_err(StorageError::io(&backup_path))?;
+        }
+    }
+    Ok(data)
+}
+
+/// Like [`load_tasks`], but never writes anything next to `path`. Used for
+/// looking into backups.
+pub fn read_tasks(path: &Path) -> Result<BoardData, StorageError> {
+    match read_document(path)? {
+        Some((document, version)) => decode_document(path, document, version),
+        None => Ok(BoardData::default()),
+    }
+}
+
+fn read_document(path: &Path) -> Result<Option<(Value, u32)>, StorageError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file = fs::File::open(path).map_err(StorageError::io(path))?;
+    let document: Value =
+        serde_json::from_reader(BufReader::new(file)).map_err(StorageError::parse(path))?;
+    let version = schema::detect_version(&document).map_err(StorageError::malformed(path))?;
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(StorageError::UnsupportedVersion {
+            path: path.to_path_buf(),
+            version,
+        });
+    }
+    Ok(Some((document, version)))
+}
+
+fn decode_document(path: &Path, document: Value, version: u32) -> Result<BoardData, StorageError> {
+    let document = schema::migrate(document, version).map_err(StorageError::malformed(path))?;
+    let file: TaskFile<Workflow, Vec<Task>> =
+        serde_json::from_value(document).map_err(StorageError::parse(path))?;
+    file.columns
+        .validate()
+        .map_err(|e| e.to_string())
+        .map_err(StorageError::malformed(path))?;
+
+    // A column removed by hand would otherwise hide its tasks
+    let mut tasks = file.tasks;
+    for task in &mut tasks {
+        if file.columns.column(&task.status).is_none() {
+            task.status = file.columns.first().id.clone();
+        }
+    }
+    Ok(BoardData {
+        workflow: file.columns,
+        sort: file.sort,
+        tasks,
+    })
+}
+
+/// How many timestamped backups [`save_tasks`] keeps next to the data file
+/// unless configured otherwise.
+pub const DEFAULT_BACKUP_COUNT: usize = 10;
+
+const BACKUP_EXTENSION: &str = "bak";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+/// A previous version of a data file, as written by [`save_tasks`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub created: NaiveDateTime,
+}
+
+/// Writes `workflow`, `sort` and `tasks` to `path` as pretty-printed JSON in
+/// the current schema.
+///
+/// The data is written to a temporary file in the same directory, flushed to
+/// disk and then renamed over `path`, so the live file is never left
+/// half-written. The directory is created if needed. Before it is replaced,
+/// the previous file is copied to a timestamped backup and all but the newest
+/// `backup_count` backups are removed.
+pub fn save_tasks(
+    path: &Path,
+    workflow: &Workflow,
+    sort: SortMode,
+    tasks: &[Task],
+    backup_count: usize,
+) -> Result<(), StorageError> {
+    let document = TaskFile {
+        schema_version: CURRENT_SCHEMA_VERSION,
+        columns: workflow,
+        sort,
+        tasks,
+    };
+    write_json_atomic(path, &document, || {
+        if backup_count > 0 && path.exists() {
+            create_backup(path)?;
+            prune_backups(path, backup_count)?;
+        }
+        Ok(())
+    })
+}
+
+/// Writes `value` to a synced temporary file next to `path` and renames it
+/// into place, calling `before_replace` just before the rename.
+pub(crate) fn write_json_atomic<T: Serialize>(
+    path: &Path,
+    value: &T,
+    before_replace: impl FnOnce() -> Result<(), StorageError>,
+) -> Result<(), StorageError> {
+    let tmp_path = sibling_path(path, |name| {
+        let mut tmp = OsString::from(".");
+        tmp.push(name);
+        tmp.push(".tmp");
+        tmp
+    });
+
+    let dir = parent_dir(path);
+    fs::create_dir_all(dir).map_err(StorageError::io(dir))?;
+
+    if let Err(e) = write_synced(&tmp_path, value) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(StorageError::Io {
+            path: tmp_path,
+            source: e,
+        });
+    }
+
+    if let Err(e) = before_replace() {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    fs::rename(&tmp_path, path).map_err(StorageError::io(path))?;
+    sync_parent_dir(path);
+    Ok(())
+}
+
+/// Copies a task file that could not be loaded to
+/// `<file>.unreadable.<timestamp>`, unless an earlier copy holds the same
+/// contents, and returns the copy. It is not a backup, so it is neither
+/// listed nor pruned with them.
+pub fn set_aside_unreadable(path: &Path) -> Result<PathBuf, StorageError> {
+    let contents = fs::read(path).map_err(StorageError::io(path))?;
+    let dir = parent_dir(path);
+    let prefix = format!(
+        "{}.unreadable.",
+        path.file_name()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+    );
+    for entry in fs::read_dir(dir).map_err(StorageError::io(dir))? {
+        let entry = entry.map_err(StorageError::io(dir))?;
+        let is_copy = entry.file_name().to_string_lossy().starts_with(&prefix);
+        if is_copy && fs::read(entry.path()).is_ok_and(|copy| copy == contents) {
+            return Ok(entry.path());
+        }
+    }
+
+    let stamp = Local::now().format(BACKUP_TIMESTAMP_FORMAT);
+    let copy_path = dir.join(format!("{}{}", prefix, stamp));
+    fs::write(&copy_path, contents).map_err(StorageError::io(&copy_path))?;
+    Ok(copy_path)
+}
+
+/// Lists the backups of `path`, newest first.
+pub fn list_backups(path: &Path) -> Result<Vec<Backup>, StorageError> {
+    let dir = parent_dir(path);
+    let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+        return Ok(Vec::new());
+    };
+    let prefix = format!("{}.", file_name);
+    let suffix = format!(".{}", BACKUP_EXTENSION);
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir).map_err(StorageError::io(dir))? {
+        let entry = entry.map_err(StorageError::io(dir))?;
+        let name = entry.file_name();
+        let Some(stamp) = name
+            .to_str()
+            .and_then(|n| n.strip_prefix(&prefix))
+            .and_then(|n| n.strip_suffix(&suffix))
+        else {
+            continue;
+        };
+        if let Ok(created) = NaiveDateTime::parse_from_str(stamp, BACKUP_TIMESTAMP_FORMAT) {
+            backups.push(Backup {
+                path: entry.path(),
+                created,
+            });
+        }
+    }
+    backups.sort_by_key(|backup| Reverse(backup.created));
+    Ok(backups)
+}
+
+fn write_synced<T: Serialize>(path: &Path, value: &T) -> Result<(), io::Error> {
+    let file = fs::File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, value)?;
+    writer.flush()?;
+    writer.get_ref().sync_all()
+}
+
+fn create_backup(path: &Path) -> Result<PathBuf, StorageError> {
+    let stamp = Local::now().format(BACKUP_TIMESTAMP_FORMAT).to_string();
+    let backup_path = sibling_path(path, |name| {
+        let mut backup = name.to_os_string();
+        backup.push(format!(".{}.{}", stamp, BACKUP_EXTENSION));
+        backup
+    });
+    match fs::copy(path, &backup_path) {
+        Ok(_) => Ok(backup_path),
+        Err(source) => Err(StorageError::Io {
+            path: backup_path,
+            source,
+        }),
+    }
+}
+
+fn prune_backups(path: &Path, backup_count: usize) -> Result<(), StorageError> {
+    for old in list_backups(path)?.into_iter().skip(backup_count) {
+        fs::remove_file(&old.path).map_err(StorageError::io(&old.path))?;
+    }
+    Ok(())
+}
+
+/// Builds a path in the same directory as `path` from its file name.
+fn sibling_path(path: &Path, make_name: impl FnOnce(&OsStr) -> OsString) -> PathBuf {
+    let name = path.file_name().unwrap_or(path.as_os_str());
+    parent_dir(path).join(make_name(name))
+}
+
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// Makes the rename durable. Not every platform lets a directory be opened
+/// and synced, so failures are ignored.
+fn sync_parent_dir(path: &Path) {
+    if let Ok(dir) = fs::File::open(parent_dir(path)) {
+        let _ = dir.sync_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use crate::model::Priority;
+    use crate::testing::{TempDir, task};
+
+    fn save(path: &Path, descriptions: &[&str], backup_count: usize) {
+        let tasks: Vec<Task> = descriptions.iter().map(|d| task(d)).collect();
+        save_tasks(
+            path,
+            &Workflow::default(),
+            SortMode::default(),
+            &tasks,
+            backup_count,
+        )
+        .unwrap();
+    }
+
+    fn descriptions(data: BoardData) -> Vec<String> {
+        data.tasks.into_iter().map(|t| t.description).collect()
+    }
+
+    #[test]
+    fn save_replaces_the_file() {
+        let dir = TempDir::new();
+        let path = dir.path().join("tasks.json");
+        save(&path, &["Buy milk"], 0);
+        save(&path, &["Call Ana", "Pay rent"], 0);
+        assert_eq!(
+            descriptions(load_tasks(&path).unwrap()),
+            ["Call Ana", "Pay rent"]
+        );
+        assert_eq!(dir.file_names(), ["tasks.json"]);
+    }
+
+    #[test]
+    fn failed_save_keeps_the_original() {
+        let dir = TempDir::new();
+        let path = dir.path().join("tasks.json");
+        save(&path, &["Buy milk"], 0);
+        let original = fs::read(&path).unwrap();
+
+        let result = write_json_atomic(&path, &["new"], || {
+            Err(StorageError::malformed(&path)("refused".to_string()))
+        });
+        assert!(matches!(result, Err(StorageError::Malformed { .. })));
+        assert_eq!(fs::read(&path).unwrap(), original);
+        assert_eq!(dir.file_names(), ["tasks.json"]);
+
+        // JSON object keys must be strings, so this fails while writing
+        let unwritable = HashMap::from([((1, 2), "x")]);
+        let result = write_json_atomic(&path, &unwritable, || Ok(()));
+        assert!(matches!(result, Err(StorageError::Io { .. })));
+        assert_eq!(fs::read(&path).unwrap(), original);
+        assert_eq!(dir.file_names(), ["tasks.json"]);
+    }
+
+    #[test]
+    fn keeps_the_newest_backups() {
+        let dir = TempDir::new();
+        let path = dir.path().join("tasks.json");
+        let saves = ["a", "b", "c", "d", "e"];
+        for count in 1..=saves.len() {
+            save(&path, &saves[..count], 3);
+            // Backups are named to the millisecond
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let backups = list_backups(&path).unwrap();
+        let lengths: Vec<usize> = backups
+            .iter()
+            .map(|backup| read_tasks(&backup.path).unwrap().tasks.len())
+            .collect();
+        assert_eq!(lengths, [4, 3, 2]);
+        assert!(backups.is_sorted_by_key(|backup| Reverse(backup.created)));
+        assert_eq!(dir.file_names().len(), 4);
+
+        // No backups are made or pruned when they are turned off
+        save(&path, &saves, 0);
+        assert_eq!(list_backups(&path).unwrap(), backups);
+    }
+
+    #[test]
+    fn only_timestamped_backups_are_listed() {
+        let dir = TempDir::new();
+        let path = dir.path().join("tasks.json");
+        save(&path, &["Buy milk"], 0);
+        save(&path, &["Buy milk"], 10);
+        for other in [
+            "tasks.json.schema-v3.bak",
+            "tasks.json.unreadable.20261014-153000-000",
+            "other.json.20261014-153000-000.bak",
+            "tasks.json.20261014-153000-000.tmp",
+        ] {
+            fs::write(dir.path().join(other), "{}").unwrap();
+        }
+
+        let backups = list_backups(&path).unwrap();
+        assert_eq!(backups.len(), 1);
+        let name = backups[0].path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("tasks.json.2") && name.ends_with(".bak"));
+
+        // Pruning leaves them alone too
+        save(&path, &["Buy milk"], 1);
+        assert_eq!(dir.file_names().len(), 6);
+    }
+
+    /// Loads `json` as a task file in a fresh directory.
+    fn load_fixture(json: &str) -> (TempDir, Result<BoardData, StorageError>) {
+        let dir = TempDir::new();
+        let path = dir.path().join("tasks.json");
+        fs::write(&path, json).unwrap();
+        let result = load_tasks(&path);
+        (dir, result)
+    }
+
+    /// Each task as `(description, status, priority, tags)`.
+    fn summary(data: &BoardData) -> Vec<(&str, &str, Priority, Vec<&str>)> {
+        data.tasks
+            .iter()
+            .map(|t| {
+                let tags = t.tags.iter().map(String::as_str).collect();
+                (
+                    t.description.as_str(),
+                    t.status.as_str(),
+                    t.priority.clone(),
+                    tags,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn migrates_every_schema_version() {
+        let v0 = r#"[
+            { "id": "2b078f97-301e-4e84-906f-b10cd593ae5a", "description": "Buy milk",
+              "status": "Todo", "category": "home", "due_time": null }
+        ]"#;
+        // The layout of the todo_data.json the app started out with
+        let v1 = r#"[
+          {
+            "id": "2b078f97-301e-4e84-906f-b10cd593ae5a",
+            "description": "Buy milk   @2025-07-07",
+            "status": "Todo",
+            "category": "home",
+            "due_time": null,
+            "priority": "Medium"
+          },
+          {
+            "id": "0bd8f9ad-ac0e-4b95-899c-32623c76db59",
+            "description": "Do assignment",
+            "status": "Done",
+            "category": null,
+            "due_time": "2025-07-08T17:00:00",
+            "priority": "High"
+          }
+        ]"#;
+        let v2 = r#"{ "schema_version": 2, "tasks": [
+            { "id": "2b078f97-301e-4e84-906f-b10cd593ae5a", "description": "Buy milk",
+              "status": "Doing", "category": "Home", "due_time": null, "priority": "Low" }
+        ] }"#;
+        let v3 = r#"{ "schema_version": 3,
+            "columns": [
+                { "id": "inbox", "name": "Inbox" },
+                { "id": "done", "name": "Done", "completed": true }
+            ],
+            "tasks": [
+                { "id": "2b078f97-301e-4e84-906f-b10cd593ae5a", "description": "Buy milk",
+                  "status": "done", "category": "home", "due_time": null, "priority": "High" }
+            ] }"#;
+        let v4 = r#"{ "schema_version": 4, "sort": "due",
+            "columns": [
+                { "id": "todo", "name": "To Do" },
+                { "id": "done", "name": "Done", "completed": true }
+            ],
+            "tasks": [
+                { "id": "2b078f97-301e-4e84-906f-b10cd593ae5a", "description": "Buy milk",
+                  "status": "todo", "tags": ["home", "shop"], "due_time": null,
+                  "priority": "Low" }
+            ] }"#;
+
+        let (_dir, data) = load_fixture(v0);
+        let data = data.unwrap();
+        assert_eq!(
+            summary(&data),
+            [("Buy milk", "todo", Priority::Medium, vec!["home"])]
+        );
+        assert_eq!(data.workflow, Workflow::default());
+
+        let (_dir, data) = load_fixture(v1);
+        let data = data.unwrap();
+        assert_eq!(
+            summary(&data),
+            [
+                (
+                    "Buy milk   @2025-07-07",
+                    "todo",
+                    Priority::Medium,
+                    vec!["home"]
+                ),
+                ("Do assignment", "done", Priority::High, vec![]),
+            ]
+        );
+        assert!(data.tasks[1].due_time.is_some());
+
+        // Categories become lowercase tags
+        let (_dir, data) = load_fixture(v2);
+        assert_eq!(
+            summary(&data.unwrap()),
+            [("Buy milk", "doing", Priority::Low, vec!["home"])]
+        );
+
+        let (_dir, data) = load_fixture(v3);
+        let data = data.unwrap();
+        assert_eq!(
+            summary(&data),
+            [("Buy milk", "done", Priority::High, vec!["home"])]
+        );
+        assert_eq!(data.workflow.columns.len(), 2);
+
+        let (_dir, data) = load_fixture(v4);
+        let data = data.unwrap();
+        assert_eq!(
+            summary(&data),
+            [("Buy milk", "todo", Priority::Low, vec!["home", "shop"])]
+        );
+        assert_eq!(data.sort, SortMode::Due);
+    }
+
+    #[test]
+    fn rejects_newer_and_malformed_files() {
+        let (_dir, result) = load_fixture(r#"{ "schema_version": 5, "tasks": [] }"#);
+        assert!(matches!(
+            result,
+            Err(StorageError::UnsupportedVersion { version: 5, .. })
+        ));
+
+        for malformed in [
+            r#"{ "tasks": [] }"#,
+            r#"{ "schema_version": "4", "tasks": [] }"#,
+            r#""tasks""#,
+            r#"[{ "description": "Buy milk", "status": "Later", "priority": "Low" }]"#,
+            r#"{ "schema_version": 4, "columns": [], "tasks": [] }"#,
+        ] {
+            let (_dir, result) = load_fixture(malformed);
+            assert!(
+                matches!(result, Err(StorageError::Malformed { .. })),
+                "{}: {:?}",
+                malformed,
+                result
+            );
+        }
+
+        let (_dir, result) = load_fixture("[{");
+        assert!(matches!(result, Err(StorageError::Parse { .. })));
+    }
+
+    #[test]
+    fn original_is_copied_once_before_migrating() {
+        let original = r#"[{ "id": "2b078f97-301e-4e84-906f-b10cd593ae5a",
+            "description": "Buy milk", "status": "Todo", "category": null,
+            "due_time": null, "priority": "Low" }]"#;
+        let (dir, result) = load_fixture(original);
+        result.unwrap();
+        let path = dir.path().join("tasks.json");
+        let backup = dir.path().join("tasks.json.schema-v1.bak");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), original);
+
+        // A later load of another version 1 file keeps the first copy
+        fs::write(&path, "[]").unwrap();
+        load_tasks(&path).unwrap();
+        assert_eq!(fs::read_to_string(&backup).unwrap(), original);
+        assert_eq!(dir.file_names(), ["tasks.json", "tasks.json.schema-v1.bak"]);
+
+        // Neither saving nor loading a current file copies anything
+        save(&path, &["Buy milk"], 0);
+        load_tasks(&path).unwrap();
+        assert_eq!(dir.file_names(), ["tasks.json", "tasks.json.schema-v1.bak"]);
+    }
+}
@@ -0,0 +1,75 @@
+//! Helpers shared by the unit tests.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use uuid::Uuid;
+
+use crate::model::Task;
+
+/// Wednesday 14 October 2026, 15:30.
+pub fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 10, 14)
+        .unwrap()
+        .and_hms_opt(15, 30, 0)
+        .unwrap()
+}
+
+/// A medium priority task in To Do with nothing but a description; set
+/// other fields with `Task { .., ..task("") }`.
+pub fn task(description: &str) -> Task {
+    Task {
+        id: Uuid::new_v4(),
+        description: description.to_string(),
+        status: "todo".to_string(),
+        tags: Vec::new(),
+        due_time: None,
+        priority: Default::default(),
+        position: 0,
+        checklist: Vec::new(),
+        notes: String::new(),
+        recurrence: None,
+        reminders: None,
+        snoozed_until: None,
+        created_at: None,
+        updated_at: None,
+    }
+}
+
+/// A directory under the system's temporary directory, removed with
+/// everything in it when dropped.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new() -> Self {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "todo-core-test-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// The names of the files in the directory, sorted.
+    pub fn file_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(&self.0)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,301 @@
+//! The columns of a board.
+//!
+//! Each board has its own ordered list of columns, stored in its task file.
+//! Tasks refer to their column by [`Column::id`], which stays the same when
+//! the column is renamed.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::boards::slugify;
+use crate::error::StorageError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub id: String,
+    pub name: String,
+    /// Tasks in a completed column are shown as done.
+    #[serde(default)]
+    pub completed: bool,
+    /// Work-in-progress limit: how many tasks the column should hold at most.
+    #[serde(default)]
+    pub wip_limit: Option<usize>,
+    /// Refuse moves into the column once `wip_limit` is reached, instead of
+    /// only warning about it.
+    #[serde(default)]
+    pub strict_limit: bool,
+}
+
+impl Column {
+    /// Whether `count` tasks are more than the column's WIP limit allows.
+    pub fn over_limit(&self, count: usize) -> bool {
+        self.wip_limit.is_some_and(|limit| count > limit)
+    }
+}
+
+/// Why a change to the columns was refused.
+#[derive(Debug)]
+pub enum WorkflowError {
+    NotFound(String),
+    EmptyName,
+    DuplicateName(String),
+    /// Removing the column would leave the board without any.
+    LastColumn,
+    Storage(StorageError),
+}
+
+impl fmt::Display for WorkflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkflowError::NotFound(name) => write!(f, "there is no column named '{}'", name),
+            WorkflowError::EmptyName => write!(f, "column names cannot be empty"),
+            WorkflowError::DuplicateName(name) => {
+                write!(f, "a column named '{}' already exists", name)
+            }
+            WorkflowError::LastColumn => write!(f, "a board needs at least one column"),
+            WorkflowError::Storage(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for WorkflowError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WorkflowError::Storage(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<StorageError> for WorkflowError {
+    fn from(e: StorageError) -> Self {
+        WorkflowError::Storage(e)
+    }
+}
+
+/// Why a task could not be moved to another column.
+#[derive(Debug)]
+pub enum MoveError {
+    /// The column has a strict WIP limit and is already full. Moving with
+    /// `force` overrides this.
+    WipLimit {
+        column: String,
+        limit: usize,
+    },
+    Storage(StorageError),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::WipLimit { column, limit } => write!(
+                f,
+                "{} is at its work-in-progress limit of {}",
+                column, limit
+            ),
+            MoveError::Storage(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MoveError::Storage(e) => Some(e),
+            MoveError::WipLimit { .. } => None,
+        }
+    }
+}
+
+impl From<StorageError> for MoveError {
+    fn from(e: StorageError) -> Self {
+        MoveError::Storage(e)
+    }
+}
+
+/// The ordered columns of a board. There is always at least one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Workflow {
+    pub columns: Vec<Column>,
+}
+
+impl Default for Workflow {
+    /// To Do, Doing and Done, with Done completed.
+    fn default() -> Self {
+        let column = |id: &str, name: &str, completed| Column {
+            id: id.to_string(),
+            name: name.to_string(),
+            completed,
+            wip_limit: None,
+            strict_limit: false,
+        };
+        Workflow {
+            columns: vec![
+                column("todo", "To Do", false),
+                column("doing", "Doing", false),
+                column("done", "Done", true),
+            ],
+        }
+    }
+}
+
+impl Workflow {
+    pub fn column(&self, id: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.id == id)
+    }
+
+    /// Looks a column up by id or case-insensitive name.
+    pub fn find(&self, id_or_name: &str) -> Option<&Column> {
+        let wanted = id_or_name.trim();
+        self.column(wanted).or_else(|| {
+            self.columns
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(wanted))
+        })
+    }
+
+    /// The column new tasks are added to.
+    pub fn first(&self) -> &Column {
+        &self.columns[0]
+    }
+
+    /// The column after `id`, wrapping around to the first one.
+    pub fn next_after(&self, id: &str) -> &Column {
+        let next = self
+            .columns
+            .iter()
+            .position(|c| c.id == id)
+            .map_or(0, |i| (i + 1) % self.columns.len());
+        &self.columns[next]
+    }
+
+    /// The first completed column, where tasks marked done go.
+    pub fn first_completed(&self) -> Option<&Column> {
+        self.columns.iter().find(|c| c.completed)
+    }
+
+    pub fn is_completed(&self, id: &str) -> bool {
+        self.column(id).is_some_and(|c| c.completed)
+    }
+
+    /// Appends a column, deriving a unique id from its name.
+    pub fn add_column(&mut self, name: &str) -> Result<&Column, WorkflowError> {
+        let name = self.check_name(name, None)?;
+        let slug = match slugify(&name) {
+            slug if slug.is_empty() => "column".to_string(),
+            slug => slug,
+        };
+        let mut id = slug.clone();
+        let mut n = 2;
+        while self.column(&id).is_some() {
+            id = format!("{}-{}", slug, n);
+            n += 1;
+        }
+
+        self.columns.push(Column {
+            id,
+            name,
+            completed: false,
+            wip_limit: None,
+            strict_limit: false,
+        });
+        Ok(self.columns.last().expect("column was just added"))
+    }
+
+    pub fn rename_column(&mut self, id: &str, name: &str) -> Result<(), WorkflowError> {
+        let name = self.check_name(name, Some(id))?;
+        self.column_mut(id)?.name = name;
+        Ok(())
+    }
+
+    pub fn set_completed(&mut self, id: &str, completed: bool) -> Result<(), WorkflowError> {
+        self.column_mut(id)?.completed = completed;
+        Ok(())
+    }
+
+    /// Sets or clears a column's WIP limit and whether it is strict.
+    pub fn set_wip_limit(
+        &mut self,
+        id: &str,
+        limit: Option<usize>,
+        strict: bool,
+    ) -> Result<(), WorkflowError> {
+        let column = self.column_mut(id)?;
+        column.wip_limit = limit;
+        column.strict_limit = strict;
+        Ok(())
+    }
+
+    /// Moves a column to `index` in the column order, or to the end if
+    /// `index` is past it.
+    pub fn move_column(&mut self, id: &str, index: usize) -> Result<(), WorkflowError> {
+        let from = self
+            .columns
+            .iter()
+            .position(|c| c.id == id)
+            .ok_or_else(|| WorkflowError::NotFound(id.to_string()))?;
+        let column = self.columns.remove(from);
+        let index = index.min(self.columns.len());
+        self.columns.insert(index, column);
+        Ok(())
+    }
+
+    /// Removes a column. Tasks still referring to it are moved by
+    /// [`AppState::set_workflow`](crate::AppState::set_workflow).
+    pub fn remove_column(&mut self, id: &str) -> Result<(), WorkflowError> {
+        if self.column(id).is_none() {
+            return Err(WorkflowError::NotFound(id.to_string()));
+        }
+        if self.columns.len() == 1 {
+            return Err(WorkflowError::LastColumn);
+        }
+        self.columns.retain(|c| c.id != id);
+        Ok(())
+    }
+
+    /// Checks that there is at least one column and that ids and names are
+    /// present and unique.
+    pub fn validate(&self) -> Result<(), WorkflowError> {
+        if self.columns.is_empty() {
+            return Err(WorkflowError::LastColumn);
+        }
+        for (i, column) in self.columns.iter().enumerate() {
+            if column.id.is_empty() || column.name.trim().is_empty() {
+                return Err(WorkflowError::EmptyName);
+            }
+            let earlier = &self.columns[..i];
+            if earlier
+                .iter()
+                .any(|c| c.id == column.id || c.name.eq_ignore_ascii_case(&column.name))
+            {
+                return Err(WorkflowError::DuplicateName(column.name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn column_mut(&mut self, id: &str) -> Result<&mut Column, WorkflowError> {
+        self.columns
+            .iter_mut()
+            .find(|c| c.id == id)
+            .ok_or_else(|| WorkflowError::NotFound(id.to_string()))
+    }
+
+    /// Trims `name` and checks that no column other than `except` has it.
+    fn check_name(&self, name: &str, except: Option<&str>) -> Result<String, WorkflowError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(WorkflowError::EmptyName);
+        }
+        if self
+            .columns
+            .iter()
+            .any(|c| Some(c.id.as_str()) != except && c.name.eq_ignore_ascii_case(name))
+        {
+            return Err(WorkflowError::DuplicateName(name.to_string()));
+        }
+        Ok(name.to_string())
+    }
+}